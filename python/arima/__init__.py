@@ -0,0 +1,5 @@
+from ._arima import Drift, Ensemble, Mean, Model, Naive, SeasonalNaive, backtest, fourier, select_fourier_k
+from . import metrics
+from .estimator import ARIMARegressor
+
+__all__ = ["Model", "Naive", "SeasonalNaive", "Drift", "Mean", "Ensemble", "ARIMARegressor", "backtest", "fourier", "select_fourier_k", "metrics"]
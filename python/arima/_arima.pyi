@@ -0,0 +1,334 @@
+"""Type stubs for the compiled `arima._arima` extension module (see `src/lib.rs`)."""
+from typing import List, Literal, Optional, Tuple, TypedDict, Union
+
+import numpy as np
+from numpy.typing import ArrayLike, NDArray
+
+SeasonalOrder = Tuple[int, int, int, int]
+
+class Model:
+    """SARIMAX forecaster implemented in Rust.
+
+    Create with keyword arguments, e.g. `Model(order=(1, 1, 1), seasonal_order=(1, 0, 1, 7), trend="n")`,
+    or one of the classmethods, e.g. `Model.sarima((1, 1, 1), (1, 0, 1, 7))`.
+    """
+
+    def __init__(
+        self,
+        order: Tuple[int, int, int] = (0, 0, 0),
+        seasonal_order: Optional[Union[SeasonalOrder, List[SeasonalOrder]]] = None,
+        seasonality: Literal["multiplicative", "additive"] = "multiplicative",
+        trend: Optional[Literal["n", "c", "t", "ct"]] = None,
+        method: Literal["recursive", "hannan-rissanen"] = "recursive",
+        future_errors: Literal["zero", "autoregressive"] = "zero",
+        exog: Optional[Union[Literal["differenced", "levels"], List[bool]]] = None,
+        regression: Literal["armax", "sarima-errors"] = "armax",
+        exog_lags: Optional[List[List[int]]] = None,
+        missing: Literal["raise", "skip"] = "raise",
+        transform: Optional[Union[float, Literal["none", "log", "guerrero", "loglik"]]] = None,
+        bias_adjust: bool = False,
+        enforce_stationarity: bool = False,
+        enforce_invertibility: bool = False,
+        allow_high_order_trend: bool = False,
+    ) -> None:
+        """
+        - order: (p, d, q)
+        - seasonal_order: (P, D, Q, s), or a list with one for each seasonal period,
+          e.g. `[(1, 0, 1, 24), (1, 1, 0, 168)]` for hourly data with daily and weekly cycles. Defaults to no seasonality.
+        - seasonality: "multiplicative" AR and MA polynomials (1 - φB)(1 - ΦB^s) as in R and statsmodels,
+          or "additive" 1 - φB - ΦB^s without cross terms
+        - trend: no trend "n", constant "c", linear time trend "t" or both "ct".
+          Defaults to "c" when d + D < 2 (a drift in levels when d + D = 1) and "n" otherwise.
+        - missing: raise on missing values (NaN) in y or x when fitting, or skip rows with missing values or lags
+          in estimation and continue forecasts after gaps. Future `x` passed to `predict` must never be missing.
+        - transform: transformation of y before differencing: "log", a Box-Cox lambda,
+          or a Box-Cox lambda chosen by Guerrero's method ("guerrero") or profile log-likelihood ("loglik")
+        - bias_adjust: back-transform point forecasts to the mean rather than the median
+        - enforce_stationarity: estimate AR coefficients with each AR factor constrained to be stationary
+        - enforce_invertibility: estimate MA coefficients with each MA factor constrained to be invertible
+        - allow_high_order_trend: allow trends that integrate to a polynomial of degree 2 or more in levels
+        - method: estimation method
+        - future_errors: future MA innovations are "zero", their expectation under the model, with past innovations
+          the one-step-ahead errors of the final coefficients. "autoregressive" instead forecasts them with an AR model
+          of the in-sample errors, as in earlier versions.
+        - exog: "differenced" (default) to difference exogenous variables along with y or "levels" to enter them undifferenced,
+          e.g. holiday dummies or interventions. A list of booleans chooses per column, True to difference.
+        - regression: "armax" puts exogenous variables next to the lags of y, "sarima-errors" fits
+          y = x β + η with SARIMA errors η, so that `coefs` for x are marginal effects in levels.
+          "sarima-errors" requires differenced exog.
+        - exog_lags: lags of each exogenous variable, 0 for the contemporaneous value,
+          e.g. `[[0, 1, 2], [1]]`. Defaults to contemporaneous values only.
+
+        Raises `ValueError` for unknown options or an invalid combination.
+        """
+
+    @classmethod
+    def sarima(cls, order: Tuple[int, int, int], seasonal_order: Union[SeasonalOrder, List[SeasonalOrder]]) -> "Model":
+        """SARIMA model.
+
+        - order: (p, d, q)
+        - seasonal_order: (P, D, Q, s), or a list with one for each seasonal period
+        """
+    @classmethod
+    def arima(cls, p: int, d: int, q: int) -> "Model":
+        """ARIMA(p, d, q) model."""
+    @classmethod
+    def arma(cls, p: int, q: int) -> "Model":
+        """ARMA(p, q) model."""
+    @classmethod
+    def autoregressive(cls, p: int) -> "Model":
+        """AR(p) model."""
+    @classmethod
+    def moving_average(cls, q: int) -> "Model":
+        """MA(q) model."""
+    def fit(self, y: ArrayLike, x: Optional[ArrayLike] = None) -> None:
+        """Estimate the coefficients on the time-series `y` with exogenous variables `x` of the same length."""
+    def append(self, y: ArrayLike, x: Optional[ArrayLike] = None, refit: bool = False) -> None:
+        """Add observations `y` and `x` after those used for fitting so that predictions follow them.
+
+        With `refit=False` the coefficients, transformation and `sigma2` are kept and only the one-step-ahead errors
+        of the new observations are added; without missing values this and `predict` only use the last observations.
+        With `refit=True` the model is estimated again on all observations.
+        """
+    def apply(self, y: ArrayLike, x: Optional[ArrayLike] = None) -> "Model":
+        """A model for another time-series `y` with the coefficients and transformation estimated by `fit`.
+
+        Nothing is re-estimated: one-step-ahead errors, differencing and integration use the new data,
+        e.g. to forecast short series with the dynamics of a long one. With a time trend its time restarts at 1.
+        """
+    def predict(self, h: int, x: Optional[ArrayLike] = None) -> NDArray[np.float64]:
+        """Forecast `h` horizons using future exogenous variables `x` of length `h`.
+
+        When every exogenous variable only enters with lags of at least k, `x` only needs the first `h - k` rows.
+        """
+    def simulate(
+        self,
+        n: int,
+        params: ArrayLike,
+        sigma: float = 1.0,
+        x: Optional[ArrayLike] = None,
+        noise: Union[str, float, None] = None,
+        seed: int = 0,
+        burn_in: int = 100,
+    ) -> NDArray[np.float64]:
+        """Simulate `n` observations from the model specification with coefficients `params`, without fitting.
+
+        `params` are in the order of `coef_names` for the columns of `x`, and `sigma` is the standard deviation
+        of the innovations on the transformed, differenced scale. `noise` is `"normal"` (the default) or the degrees
+        of freedom of a Student's t scaled to the same standard deviation. The first `burn_in` observations,
+        with `x` zero, are simulated and dropped. The same `seed` gives the same series.
+        """
+    def predict_interval(
+        self, h: int, x: Optional[ArrayLike] = None, level: float = 0.95
+    ) -> Tuple[NDArray[np.float64], NDArray[np.float64]]:
+        """Lower and upper bounds of prediction intervals with coverage `level` for `h` horizons.
+
+        Assumes normal errors on the transformed scale; bounds are transformed back to the original scale.
+        """
+    def predict_range(
+        self, start: int, end: int, dynamic: Optional[int] = None, x: Optional[ArrayLike] = None
+    ) -> NDArray[np.float64]:
+        """Predictions for `start, ..., end` on the original scale, which may continue past the end of `y`.
+
+        Before index `start + dynamic` observations feed the lags (one-step-ahead predictions), from it on predictions do,
+        like statsmodels' `get_prediction(start, end, dynamic=dynamic)`. Defaults to the end of `y`.
+        `x` holds future exogenous variables for the `end + 1 - len(y)` horizons past the end of `y`.
+        """
+    def forecast(
+        self, y: ArrayLike, h: int, x: Optional[ArrayLike] = None, x_future: Optional[ArrayLike] = None
+    ) -> NDArray[np.float64]:
+        """`fit` followed by `predict`."""
+    def fit_predict(
+        self, y: ArrayLike, h: int, x: Optional[ArrayLike] = None, x_future: Optional[ArrayLike] = None
+    ) -> NDArray[np.float64]:
+        """Alias of `forecast`."""
+    def fitted_values(self) -> NDArray[np.float64]:
+        """One-step-ahead predictions of `y` used for fitting, on the original scale and aligned with `y`.
+
+        NaN for the first observations lost to differencing and lags and where `y` is missing.
+        """
+    def residuals(self) -> NDArray[np.float64]:
+        """`y` used for fitting minus `fitted_values()`."""
+    @property
+    def is_fitted(self) -> bool:
+        """Whether `fit` has been called."""
+    @property
+    def n_exog(self) -> int:
+        """Number of exogenous variables used for fitting."""
+    @property
+    def coef_names(self) -> List[str]:
+        """Names of the coefficients, in the same order as `coefs`."""
+    @property
+    def coefs(self) -> NDArray[np.float64]:
+        """Coefficients estimated by `fit`."""
+    @property
+    def aic(self) -> float:
+        """Akaike information criterion of the one-step-ahead errors on the transformed, differenced scale."""
+    @property
+    def aicc(self) -> float:
+        """AIC corrected for small samples."""
+    @property
+    def ar_roots(self) -> NDArray[np.complex128]:
+        """Roots of the AR polynomial of the differenced series, outside the unit circle when stationary."""
+    @property
+    def ma_roots(self) -> NDArray[np.complex128]:
+        """Roots of the MA polynomial of the differenced series, outside the unit circle when invertible."""
+    @property
+    def is_stationary(self) -> bool:
+        """Whether all AR roots lie outside the unit circle."""
+    @property
+    def is_invertible(self) -> bool:
+        """Whether all MA roots lie outside the unit circle."""
+    def __repr__(self) -> str:
+        """e.g. `Model(SARIMAX(1,1,1)(1,0,1,7), fitted=True, n_exog=2)`"""
+    def __str__(self) -> str:
+        """e.g. `SARIMAX(1,1,1)(1,0,1,7)`"""
+
+def fourier(periods: List[float], k: List[int], start: int, n: int) -> NDArray[np.float64]:
+    """Fourier terms sin(2πkt/period), cos(2πkt/period) for t = start, ..., start + n - 1, shape (n, columns).
+
+    - periods: seasonal periods, e.g. `[7, 365.25]`
+    - k: number of sine and cosine pairs for each period, at most period / 2
+    - start: 0 for fitting and the length of y for predicting
+    """
+
+def select_fourier_k(
+    model: Model, y: ArrayLike, periods: List[float], max_k: List[int], x: Optional[ArrayLike] = None
+) -> List[int]:
+    """k for each period minimising the AICc of `model` fit with the Fourier terms, trying every k from 1 to `max_k`.
+
+    `model` is the specification, usually without seasonal terms, and is not fitted itself.
+    Other exogenous variables `x` are placed before the Fourier terms.
+    """
+
+class _Benchmark:
+    def fit(self, y: ArrayLike, x: Optional[ArrayLike] = None) -> None:
+        """Fit to the time-series `y`; exogenous variables `x` are ignored and missing values (NaN) skipped."""
+    def predict(self, h: int, x: Optional[ArrayLike] = None) -> NDArray[np.float64]:
+        """Forecast `h` horizons."""
+    def forecast(
+        self, y: ArrayLike, h: int, x: Optional[ArrayLike] = None, x_future: Optional[ArrayLike] = None
+    ) -> NDArray[np.float64]:
+        """`fit` followed by `predict`."""
+
+class Naive(_Benchmark):
+    """Every forecast is the last observation."""
+
+    def __init__(self) -> None: ...
+
+class SeasonalNaive(_Benchmark):
+    """Every forecast is the last observation of the same season, `s` periods before."""
+
+    def __init__(self, s: int) -> None: ...
+
+class Drift(_Benchmark):
+    """The last observation plus h times the average change between the first and last observations."""
+
+    def __init__(self) -> None: ...
+
+class Mean(_Benchmark):
+    """Every forecast is the mean of the observations."""
+
+    def __init__(self) -> None: ...
+
+class Ensemble:
+    """Forecast combination of several models, e.g. SARIMA models with different orders.
+
+    - models: `Model` or benchmark forecasters, their own data and coefficients are not used
+    - weights: "equal", "inverse-error" (inverse mean squared error) or "stacking" (non-negative weights summing to one
+      that minimise the squared error of the combination), from one-step-ahead forecasts of the last `validation`
+      observations with the models fit before them
+    - seed: of the simulated paths for `predict_interval`
+    """
+
+    def __init__(
+        self,
+        models: List["Forecaster"],
+        weights: Literal["equal", "inverse-error", "stacking"] = "equal",
+        validation: int = 0,
+        seed: int = 0,
+    ) -> None: ...
+    def fit(self, y: ArrayLike, x: Optional[ArrayLike] = None) -> None:
+        """Choose the weights, then fit every model to `y`."""
+    def predict(self, h: int, x: Optional[ArrayLike] = None) -> NDArray[np.float64]:
+        """Weighted average of the forecasts of the models."""
+    def predict_interval(
+        self, h: int, x: Optional[ArrayLike] = None, level: float = 0.95, n_paths: int = 1000
+    ) -> Tuple[NDArray[np.float64], NDArray[np.float64]]:
+        """Quantiles of `n_paths` paths simulated from the mixture of the forecast distributions of the models."""
+    def forecast(
+        self, y: ArrayLike, h: int, x: Optional[ArrayLike] = None, x_future: Optional[ArrayLike] = None
+    ) -> NDArray[np.float64]:
+        """`fit` followed by `predict`."""
+    @property
+    def weights(self) -> NDArray[np.float64]:
+        """Weight of each model, chosen by `fit`."""
+
+Forecaster = Union[Model, Naive, SeasonalNaive, Drift, Mean, Ensemble]
+
+class BacktestResult(TypedDict):
+    """Forecasts and observations with one row per origin and one column per horizon, NaN past the end of `y`."""
+
+    origins: NDArray[np.uint64]
+    forecasts: NDArray[np.float64]
+    actuals: NDArray[np.float64]
+    errors: NDArray[np.float64]
+    horizon_mae: NDArray[np.float64]
+    horizon_rmse: NDArray[np.float64]
+    mae: float
+    rmse: float
+    mape: float
+    smape: float
+    bias: float
+
+def backtest(
+    model: Forecaster,
+    y: ArrayLike,
+    initial: int,
+    horizon: int,
+    x: Optional[ArrayLike] = None,
+    step: int = 1,
+    refit_every: int = 1,
+    window: Literal["expanding", "sliding"] = "expanding",
+    n_jobs: int = 0,
+) -> BacktestResult:
+    """Rolling-origin forecasts of `horizon` steps from origins `initial`, `initial + step`, ... of `y`.
+
+    - model: `Model` or benchmark fit at each origin, its own data and coefficients are not used
+    - refit_every: origins between estimations, in between the coefficients are applied to the new window
+    - window: fit to all observations before the origin ("expanding") or the last `initial` ("sliding")
+    - n_jobs: number of threads, 0 for one per available core
+    """
+
+def mae(actual: ArrayLike, forecast: ArrayLike) -> float:
+    """Mean absolute error."""
+
+def rmse(actual: ArrayLike, forecast: ArrayLike) -> float:
+    """Root mean squared error."""
+
+def mape(actual: ArrayLike, forecast: ArrayLike) -> float:
+    """Mean absolute percentage error, in percent."""
+
+def smape(actual: ArrayLike, forecast: ArrayLike) -> float:
+    """Symmetric mean absolute percentage error, 200 |a - f| / (|a| + |f|) on average."""
+
+def bias(actual: ArrayLike, forecast: ArrayLike) -> float:
+    """Mean error, the mean of actual - forecast, negative when forecasts are too high."""
+
+def mase(actual: ArrayLike, forecast: ArrayLike, train: ArrayLike, s: int = 1) -> float:
+    """Mean absolute scaled error, relative to the in-sample MAE of the seasonal naive forecast of `train`."""
+
+def rmsse(actual: ArrayLike, forecast: ArrayLike, train: ArrayLike, s: int = 1) -> float:
+    """Root mean squared scaled error, relative to the in-sample RMSE of the seasonal naive forecast of `train`."""
+
+def pinball(actual: ArrayLike, quantile: ArrayLike, tau: float) -> float:
+    """Pinball loss of forecasts of the `tau` quantile."""
+
+def coverage(actual: ArrayLike, lower: ArrayLike, upper: ArrayLike) -> float:
+    """Share of actual values within `[lower, upper]`."""
+
+def winkler(actual: ArrayLike, lower: ArrayLike, upper: ArrayLike, level: float = 0.95) -> float:
+    """Interval width plus 2 / (1 - level) times the distance of actual values outside the interval, on average."""
+
+def crps(actual: ArrayLike, paths: ArrayLike) -> float:
+    """Continuous ranked probability score of simulated `paths`, one row per path and one column per horizon."""
@@ -0,0 +1,150 @@
+"""scikit-learn compatible estimator built on top of `Model`.
+
+scikit-learn is optional: when it is installed the estimator inherits from its
+`BaseEstimator` and `RegressorMixin` so it works with pipelines, `clone` and
+`GridSearchCV`. Without it, equivalent `get_params`, `set_params` and `score`
+methods are provided.
+"""
+from typing import Any, Dict, List, Optional, Tuple, Union
+import inspect
+
+import numpy as np
+from numpy.typing import ArrayLike, NDArray
+
+from ._arima import Model
+
+try:
+    from sklearn.base import BaseEstimator, RegressorMixin
+except ImportError:  # pragma: no cover - depends on the environment
+
+    class BaseEstimator:  # type: ignore[no-redef]
+        """Minimal stand-in for `sklearn.base.BaseEstimator`."""
+
+        @classmethod
+        def _get_param_names(cls) -> list:
+            signature = inspect.signature(cls.__init__)
+            return sorted(name for name in signature.parameters if name != "self")
+
+        def get_params(self, deep: bool = True) -> Dict[str, Any]:
+            return {name: getattr(self, name) for name in self._get_param_names()}
+
+        def set_params(self, **params: Any) -> "BaseEstimator":
+            valid = self._get_param_names()
+            for name, value in params.items():
+                if name not in valid:
+                    raise ValueError(f"Invalid parameter {name!r} for estimator {type(self).__name__}.")
+                setattr(self, name, value)
+            return self
+
+    class RegressorMixin:  # type: ignore[no-redef]
+        """Minimal stand-in for `sklearn.base.RegressorMixin`."""
+
+        def score(self, X: ArrayLike, y: ArrayLike, sample_weight: Optional[ArrayLike] = None) -> float:
+            y = np.asarray(y, dtype=np.float64)
+            y_pred = self.predict(X)
+            weights = np.ones_like(y) if sample_weight is None else np.asarray(sample_weight, dtype=np.float64)
+            residual = np.sum(weights * (y - y_pred) ** 2)
+            total = np.sum(weights * (y - np.average(y, weights=weights)) ** 2)
+            return 1.0 - residual / total
+
+
+def _as_2d(X: Optional[ArrayLike], n_rows: int) -> NDArray[np.float64]:
+    if X is None:
+        return np.zeros((n_rows, 0))
+    X = np.asarray(X, dtype=np.float64)
+    if X.ndim == 1:
+        X = X.reshape(-1, 1)
+    return np.ascontiguousarray(X)
+
+
+class ARIMARegressor(RegressorMixin, BaseEstimator):
+    """SARIMAX forecaster following the scikit-learn estimator API.
+
+    - order: (p, d, q)
+    - seasonal_order: (P, D, Q, s), or a list with one for each seasonal period
+    - trend: "n", "c", "t" or "ct", defaults to "c" when d + D < 2 and "n" otherwise
+    - method: "recursive" or "hannan-rissanen"
+
+    The other keywords are those of `Model`, so that all of them can be tuned with `GridSearchCV`.
+
+    `fit(X, y)` trains on the time-series `y` with exogenous variables `X`
+    (which may have zero columns). `predict(X)` forecasts `len(X)` horizons
+    following the end of the training series, using `X` as the future
+    exogenous variables. Without exogenous variables only the length of `X` is used.
+    """
+
+    def __init__(
+        self,
+        order: Tuple[int, int, int] = (1, 0, 0),
+        seasonal_order: Union[Tuple[int, int, int, int], List[Tuple[int, int, int, int]]] = (0, 0, 0, 0),
+        trend: Optional[str] = None,
+        method: str = "recursive",
+        seasonality: str = "multiplicative",
+        future_errors: str = "zero",
+        exog: Optional[Union[str, List[bool]]] = None,
+        regression: str = "armax",
+        exog_lags: Optional[List[List[int]]] = None,
+        missing: str = "raise",
+        transform: Optional[Union[float, str]] = None,
+        bias_adjust: bool = False,
+        enforce_stationarity: bool = False,
+        enforce_invertibility: bool = False,
+        allow_high_order_trend: bool = False,
+    ):
+        self.order = order
+        self.seasonal_order = seasonal_order
+        self.trend = trend
+        self.method = method
+        self.seasonality = seasonality
+        self.future_errors = future_errors
+        self.exog = exog
+        self.regression = regression
+        self.exog_lags = exog_lags
+        self.missing = missing
+        self.transform = transform
+        self.bias_adjust = bias_adjust
+        self.enforce_stationarity = enforce_stationarity
+        self.enforce_invertibility = enforce_invertibility
+        self.allow_high_order_trend = allow_high_order_trend
+
+    def fit(self, X: Optional[ArrayLike], y: ArrayLike) -> "ARIMARegressor":
+        y = np.ascontiguousarray(y, dtype=np.float64)
+        if y.ndim != 1:
+            raise ValueError(f"y should be 1 dimensional, got shape {y.shape}.")
+        X = _as_2d(X, len(y))
+        if len(X) != len(y):
+            raise ValueError(f"X has {len(X)} rows. It should have {len(y)}.")
+
+        self.model_ = Model(
+            order=tuple(self.order),
+            seasonal_order=self.seasonal_order,
+            trend=self.trend,
+            method=self.method,
+            seasonality=self.seasonality,
+            future_errors=self.future_errors,
+            exog=self.exog,
+            regression=self.regression,
+            exog_lags=self.exog_lags,
+            missing=self.missing,
+            transform=self.transform,
+            bias_adjust=self.bias_adjust,
+            enforce_stationarity=self.enforce_stationarity,
+            enforce_invertibility=self.enforce_invertibility,
+            allow_high_order_trend=self.allow_high_order_trend,
+        )
+        self.model_.fit(y=y, x=X)
+        self.n_features_in_ = X.shape[1]
+        return self
+
+    def predict(self, X: ArrayLike) -> NDArray[np.float64]:
+        if not hasattr(self, "model_"):
+            raise ValueError(f"This {type(self).__name__} instance is not fitted yet. Call 'fit' first.")
+        if X is None:
+            raise ValueError("X is required: its length is the forecast horizon.")
+        if self.n_features_in_ == 0:
+            h = len(X)
+            return self.model_.predict(h=h, x=np.zeros((h, 0)))
+        X = _as_2d(X, 0)
+        if X.shape[1] != self.n_features_in_:
+            raise ValueError(f"X has {X.shape[1]} columns. It should have {self.n_features_in_}.")
+        return self.model_.predict(h=len(X), x=X)
@@ -0,0 +1,15 @@
+"""Forecast accuracy metrics implemented in Rust.
+
+Pairs where the actual value or forecast is NaN are skipped, errors are actual - forecast
+and percentage metrics are in percent.
+
+```python
+from arima import metrics
+
+metrics.mase(y_test, preds, train=y_train, s=7)
+metrics.winkler(y_test, lower, upper, level=0.95)
+```
+"""
+from ._arima import bias, coverage, crps, mae, mape, mase, pinball, rmse, rmsse, smape, winkler
+
+__all__ = ["mae", "rmse", "mape", "smape", "bias", "mase", "rmsse", "pinball", "coverage", "winkler", "crps"]
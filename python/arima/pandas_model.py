@@ -0,0 +1,194 @@
+"""pandas-aware wrapper around `Model` that preserves the time index.
+
+`y` and `x` may be given as pandas `Series`/`DataFrame`. The frequency of the
+index is inferred when fitting so forecasts come back as a `Series` indexed by
+the future dates, and exogenous column names are used to label coefficients.
+"""
+import re
+from typing import List, Optional, Union
+
+import numpy as np
+import pandas as pd
+
+from ._arima import Model
+
+Exog = Union[pd.DataFrame, pd.Series, np.ndarray]
+
+
+def _exog_values(x: Optional[Exog], columns: Optional[List[str]], n_rows: int) -> np.ndarray:
+    if x is None:
+        return np.zeros((n_rows, 0))
+    if isinstance(x, pd.Series):
+        x = x.to_frame()
+    if isinstance(x, pd.DataFrame):
+        if columns is not None and list(x.columns) != columns:
+            missing = [c for c in columns if c not in x.columns]
+            if missing:
+                raise ValueError(f"x is missing columns: {missing}.")
+            x = x[columns]
+        x = x.to_numpy(dtype=np.float64)
+    x = np.asarray(x, dtype=np.float64)
+    if x.ndim == 1:
+        x = x.reshape(-1, 1)
+    return np.ascontiguousarray(x)
+
+
+def _exog_name(name: str, exog_names: List[str]) -> str:
+    """x{j} and its lags x{j}.L{l} named after column j of x"""
+    match = re.fullmatch(r"x(\d+)(\.L\d+)?", name)
+    if match is None or int(match.group(1)) >= len(exog_names):
+        return name
+    return exog_names[int(match.group(1))] + (match.group(2) or "")
+
+
+def _future_index(index: pd.Index, freq, h: int) -> pd.Index:
+    if isinstance(index, pd.DatetimeIndex):
+        return pd.date_range(start=index[-1] + freq, periods=h, freq=freq, name=index.name)
+    if isinstance(index, pd.PeriodIndex):
+        return pd.period_range(start=index[-1] + 1, periods=h, freq=index.freq, name=index.name)
+    step = freq if freq is not None else 1
+    start = index[-1] + step
+    return pd.RangeIndex(start=start, stop=start + step * h, step=step, name=index.name)
+
+
+def _infer_freq(index: pd.Index):
+    if isinstance(index, pd.DatetimeIndex):
+        freq = index.freq or pd.infer_freq(index)
+        if freq is None:
+            raise ValueError("Could not infer the frequency of the index of y. Set it with `y.asfreq(...)`.")
+        return pd.tseries.frequencies.to_offset(freq)
+    if isinstance(index, pd.PeriodIndex):
+        return index.freq
+    if isinstance(index, pd.RangeIndex):
+        return index.step
+    if pd.api.types.is_integer_dtype(index):
+        steps = np.unique(np.diff(index.to_numpy()))
+        if len(steps) > 1:
+            raise ValueError("Integer index of y should be evenly spaced.")
+        return int(steps[0]) if len(steps) else 1
+    raise ValueError(f"Unsupported index type for y: {type(index).__name__}.")
+
+
+class PandasModel:
+    """Wraps a `Model` so that it accepts and returns pandas objects.
+
+    ```python
+    m = PandasModel(Model.sarima(order=(1, 1, 1), seasonal_order=(1, 0, 1, 7)))
+    m.fit(y=sales, x=promotions)
+    preds = m.predict(h=14, x=future_promotions)  # Series indexed by the next 14 dates
+    ```
+    """
+
+    def __init__(self, model: Model):
+        self.model = model
+        self.index: Optional[pd.Index] = None
+        self.freq = None
+        self.name = None
+        self.exog_names: Optional[List[str]] = None
+
+    def fit(self, y: Union[pd.Series, np.ndarray], x: Optional[Exog] = None) -> "PandasModel":
+        if not isinstance(y, pd.Series):
+            y = pd.Series(np.asarray(y, dtype=np.float64))
+        self.index = y.index
+        self.freq = _infer_freq(y.index)
+        self.name = y.name
+
+        if isinstance(x, pd.Series):
+            x = x.to_frame()
+        if isinstance(x, pd.DataFrame):
+            if not x.index.equals(y.index):
+                raise ValueError("x should have the same index as y.")
+            self.exog_names = [str(c) for c in x.columns]
+        elif x is not None:
+            self.exog_names = [f"x{j}" for j in range(_exog_values(x, None, len(y)).shape[1])]
+        else:
+            self.exog_names = []
+
+        self.model.fit(y=y.to_numpy(dtype=np.float64), x=_exog_values(x, None, len(y)))
+        return self
+
+    def append(self, y: Union[pd.Series, np.ndarray], x: Optional[Exog] = None, refit: bool = False) -> "PandasModel":
+        """adds observations following the training series, see `Model.append`.
+        The index of a Series `y` should continue the training index, and that of `x` should match it."""
+        if self.index is None:
+            raise ValueError("Model must be fit before append.")
+        if not isinstance(y, pd.Series):
+            y = pd.Series(np.asarray(y, dtype=np.float64), index=self.future_index(len(y)))
+        elif not y.index.equals(self.future_index(len(y))):
+            raise ValueError("The index of y should continue the training index, without gaps or overlaps.")
+        if isinstance(x, (pd.Series, pd.DataFrame)) and not x.index.equals(y.index):
+            raise ValueError("x should have the same index as y.")
+
+        columns = self.exog_names if isinstance(x, pd.DataFrame) else None
+        self.model.append(y=y.to_numpy(dtype=np.float64), x=_exog_values(x, columns, len(y)), refit=refit)
+        self.index = self.index.append(y.index)
+        return self
+
+    def apply(self, y: Union[pd.Series, np.ndarray], x: Optional[Exog] = None) -> "PandasModel":
+        """model for another series with the coefficients estimated on the training series, see `Model.apply`"""
+        if self.index is None:
+            raise ValueError("Model must be fit before apply.")
+        if not isinstance(y, pd.Series):
+            y = pd.Series(np.asarray(y, dtype=np.float64))
+        if isinstance(x, pd.Series):
+            x = x.to_frame()
+        if isinstance(x, pd.DataFrame) and not x.index.equals(y.index):
+            raise ValueError("x should have the same index as y.")
+
+        columns = self.exog_names if isinstance(x, pd.DataFrame) else None
+        applied = PandasModel(self.model.apply(y=y.to_numpy(dtype=np.float64), x=_exog_values(x, columns, len(y))))
+        applied.index = y.index
+        applied.freq = _infer_freq(y.index)
+        applied.name = y.name
+        applied.exog_names = self.exog_names
+        return applied
+
+    def predict(self, h: int, x: Optional[Exog] = None) -> pd.Series:
+        if self.index is None:
+            raise ValueError("Model must be fit before predict.")
+        columns = self.exog_names if isinstance(x, pd.DataFrame) else None
+        values = self.model.predict(h=h, x=_exog_values(x, columns, h))
+        return pd.Series(values, index=self.future_index(h), name=self.name)
+
+    def predict_interval(self, h: int, x: Optional[Exog] = None, level: float = 0.95) -> pd.DataFrame:
+        """lower and upper bounds of the prediction intervals indexed by the future dates"""
+        if self.index is None:
+            raise ValueError("Model must be fit before predict.")
+        columns = self.exog_names if isinstance(x, pd.DataFrame) else None
+        lower, upper = self.model.predict_interval(h=h, x=_exog_values(x, columns, h), level=level)
+        return pd.DataFrame({"lower": lower, "upper": upper}, index=self.future_index(h))
+
+    def predict_range(self, start: int, end: int, dynamic: Optional[int] = None, x: Optional[Exog] = None) -> pd.Series:
+        """predictions for positions `start, ..., end` of the training series and beyond, see `Model.predict_range`"""
+        if self.index is None:
+            raise ValueError("Model must be fit before predict.")
+        h = max(end + 1 - len(self.index), 0)
+        columns = self.exog_names if isinstance(x, pd.DataFrame) else None
+        values = self.model.predict_range(start, end, dynamic, None if x is None else _exog_values(x, columns, h))
+        index = self.index.append(self.future_index(h))[start:end + 1]
+        return pd.Series(values, index=index, name=self.name)
+
+    def forecast(self, y: pd.Series, h: int, x: Optional[Exog] = None, x_future: Optional[Exog] = None) -> pd.Series:
+        return self.fit(y, x).predict(h, x_future)
+
+    def future_index(self, h: int) -> pd.Index:
+        """index of the `h` periods following the end of the training series"""
+        return _future_index(self.index, self.freq, h)
+
+    def fitted_values(self) -> pd.Series:
+        """one-step-ahead predictions indexed like the training series"""
+        if self.index is None:
+            raise ValueError("Model must be fit before fitted values.")
+        return pd.Series(self.model.fitted_values(), index=self.index, name=self.name)
+
+    def residuals(self) -> pd.Series:
+        """training series minus the fitted values"""
+        if self.index is None:
+            raise ValueError("Model must be fit before residuals.")
+        return pd.Series(self.model.residuals(), index=self.index, name=self.name)
+
+    @property
+    def coefs(self) -> pd.Series:
+        """coefficients labelled by term, with exogenous terms named after the columns of `x`"""
+        names = [_exog_name(name, self.exog_names or []) for name in self.model.coef_names]
+        return pd.Series(self.model.coefs, index=names)
@@ -0,0 +1,274 @@
+use std::str::FromStr;
+use std::thread;
+use numpy::ndarray::{Array, Array1, Array2, s};
+use crate::forecaster::Forecaster;
+use crate::metrics;
+
+
+/// which observations before each forecast origin are used for fitting
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Window {
+    // all observations from the start of y
+    Expanding,
+    // the last `initial` observations
+    Sliding
+}
+
+impl FromStr for Window {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "expanding" => Ok(Window::Expanding),
+            "sliding" => Ok(Window::Sliding),
+            _ => Err(format!("Unknown window: {}. It should be one of: expanding, sliding.", s))
+        }
+    }
+}
+
+/// Rolling-origin evaluation of a forecaster, forecasting `horizon` steps from origins `initial`, `initial + step`, ...
+/// with the model fit to the observations before each origin.
+///
+/// ```ignore
+/// let result = Backtest::new(100, 14).step(7).refit_every(4).window(Window::Sliding).run(&model, &y, None);
+/// result.horizon_rmse();
+/// ```
+#[derive(PartialEq, Clone, Debug)]
+pub struct Backtest {
+    // initial: observations before the first origin, and the length of sliding windows
+    // horizon: steps forecast from each origin
+    // step: observations between origins
+    // refit_every: origins between estimations, in between the coefficients are applied to the new window
+    // window: expanding or sliding window of observations used for fitting
+    // n_jobs: number of threads, 0 for one per available core
+    initial: usize,
+    horizon: usize,
+    step: usize,
+    refit_every: usize,
+    window: Window,
+    n_jobs: usize
+}
+
+/// forecasts and observations of a backtest, with one row per origin and one column per horizon.
+/// Horizons past the end of y are NaN.
+#[derive(PartialEq, Clone, Debug)]
+pub struct BacktestResult {
+    pub origins: Vec<usize>,
+    pub forecasts: Array2<f64>,
+    pub actuals: Array2<f64>
+}
+
+impl Backtest {
+    /// - initial: observations before the first origin, and the length of sliding windows
+    /// - horizon: steps forecast from each origin
+    pub fn new(initial: usize, horizon: usize) -> Self {
+        if initial == 0 || horizon == 0 {
+            panic!("initial and horizon must be at least 1, got {} and {}.", initial, horizon);
+        }
+        Self {initial, horizon, step: 1, refit_every: 1, window: Window::Expanding, n_jobs: 0}
+    }
+
+    pub fn step(mut self, step: usize) -> Self {
+        if step == 0 {
+            panic!("step must be at least 1.");
+        }
+        self.step = step;
+        self
+    }
+
+    pub fn refit_every(mut self, refit_every: usize) -> Self {
+        if refit_every == 0 {
+            panic!("refit_every must be at least 1.");
+        }
+        self.refit_every = refit_every;
+        self
+    }
+
+    pub fn window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn n_jobs(mut self, n_jobs: usize) -> Self {
+        self.n_jobs = n_jobs;
+        self
+    }
+
+    /// forecast origins, the index of y of the first forecast
+    fn origins(&self, n: usize) -> Vec<usize> {
+        (self.initial..n).step_by(self.step).collect()
+    }
+
+    /// - forecaster: specification to fit at each origin, e.g. a `Model`, its data and parameters are not used
+    /// - y: timeseries
+    /// - x: exogenous variables, same length as y
+    ///
+    /// Origins are split into groups of refit_every, each fit once and then forecast with `Forecaster::apply`.
+    /// Groups run in parallel.
+    pub fn run(&self, forecaster: &dyn Forecaster, y: &Array1<f64>, x: Option<&Array2<f64>>) -> BacktestResult {
+        let n = y.len();
+        if self.initial >= n {
+            panic!("y is length: {}. It should be longer than the initial window: {}.", n, self.initial);
+        }
+        let x = x.map_or_else(|| Array::zeros((n, 0)), |x| x.to_owned());
+        if x.shape()[0] != n {
+            panic!("x is length: {}. It should be length: {}.", x.shape()[0], n);
+        }
+
+        let origins = self.origins(n);
+        let groups: Vec<&[usize]> = origins.chunks(self.refit_every).collect();
+        let n_jobs = match self.n_jobs {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n_jobs => n_jobs
+        };
+        let per_thread = groups.len().div_ceil(n_jobs);
+
+        let rows: Vec<Array1<f64>> = thread::scope(|scope| {
+            let handles: Vec<_> = groups
+                .chunks(per_thread)
+                .map(|groups| scope.spawn(|| {
+                    groups.iter().flat_map(|group| self.forecast_group(forecaster, group, y, &x)).collect::<Vec<_>>()
+                }))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        });
+
+        let mut forecasts = Array::from_elem((origins.len(), self.horizon), f64::NAN);
+        let mut actuals = Array::from_elem((origins.len(), self.horizon), f64::NAN);
+        for (i, (&origin, row)) in origins.iter().zip(rows).enumerate() {
+            forecasts.slice_mut(s![i, ..row.len()]).assign(&row);
+            actuals.slice_mut(s![i, ..row.len()]).assign(&y.slice(s![origin..origin + row.len()]));
+        }
+        BacktestResult {origins, forecasts, actuals}
+    }
+
+    /// forecasts from each origin of the group, fitting at the first
+    fn forecast_group(&self, forecaster: &dyn Forecaster, group: &[usize], y: &Array1<f64>, x: &Array2<f64>) -> Vec<Array1<f64>> {
+        let mut fitted = forecaster.clone_box();
+        group
+            .iter()
+            .enumerate()
+            .map(|(i, &origin)| {
+                let start = match self.window {
+                    Window::Expanding => 0,
+                    Window::Sliding => origin - self.initial
+                };
+                let h = self.horizon.min(y.len() - origin);
+                let (y_fit, x_fit) = (y.slice(s![start..origin]).to_owned(), x.slice(s![start..origin, ..]).to_owned());
+                let x_future = x.slice(s![origin..origin + h, ..]).to_owned();
+                if i == 0 {
+                    fitted.fit(&y_fit, Some(&x_fit));
+                    fitted.predict(h, Some(&x_future))
+                } else {
+                    fitted.apply(&y_fit, Some(&x_fit)).predict(h, Some(&x_future))
+                }
+            })
+            .collect()
+    }
+}
+
+impl BacktestResult {
+    /// actuals minus forecasts
+    pub fn errors(&self) -> Array2<f64> {
+        &self.actuals - &self.forecasts
+    }
+
+    /// metric(actual, forecast) of each horizon over all origins, e.g. `result.horizon_metric(metrics::mae)`
+    pub fn horizon_metric(&self, metric: fn(&Array1<f64>, &Array1<f64>) -> f64) -> Array1<f64> {
+        Array1::from_iter((0..self.forecasts.shape()[1]).map(|j| {
+            metric(&self.actuals.column(j).to_owned(), &self.forecasts.column(j).to_owned())
+        }))
+    }
+
+    /// metric(actual, forecast) over all origins and horizons, e.g. `result.metric(metrics::rmse)`
+    pub fn metric(&self, metric: fn(&Array1<f64>, &Array1<f64>) -> f64) -> f64 {
+        metric(&self.actuals.iter().copied().collect(), &self.forecasts.iter().copied().collect())
+    }
+
+    /// mean absolute error of each horizon
+    pub fn horizon_mae(&self) -> Array1<f64> {
+        self.horizon_metric(metrics::mae)
+    }
+
+    /// root mean squared error of each horizon
+    pub fn horizon_rmse(&self) -> Array1<f64> {
+        self.horizon_metric(metrics::rmse)
+    }
+
+    /// mean absolute error over all origins and horizons
+    pub fn mae(&self) -> f64 {
+        self.metric(metrics::mae)
+    }
+
+    /// root mean squared error over all origins and horizons
+    pub fn rmse(&self) -> f64 {
+        self.metric(metrics::rmse)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarks::SeasonalNaive;
+    use crate::model::Model;
+    use numpy::ndarray::arr1;
+
+    fn ar1(n: usize) -> Array1<f64> {
+        let mut y: Array1<f64> = Array::zeros(n);
+        for t in 1..n {
+            y[t] = 2. + 0.5 * y[t - 1];
+        }
+        y
+    }
+
+    #[test]
+    fn backtest_origins() {
+        let result = Backtest::new(20, 3).step(4).run(&Model::autoregressive(1), &ar1(30), None);
+        assert_eq!(result.origins, vec![20, 24, 28]);
+        assert_eq!(result.forecasts.shape(), &[3, 3]);
+        // the last origin only has two observations to forecast
+        assert!(result.forecasts[[2, 2]].is_nan() && result.actuals[[2, 2]].is_nan());
+        assert_eq!(result.actuals.row(1), arr1(&[ar1(30)[24], ar1(30)[25], ar1(30)[26]]));
+    }
+
+    #[test]
+    fn backtest_exact_model() {
+        // a sinusoid is an AR(2) with intercept, forecast exactly refitting or not, with either window
+        let y = Array::range(0., 60., 1.).mapv(|t: f64| 10. + 5. * (t / 3.).sin());
+        for window in [Window::Expanding, Window::Sliding] {
+            for refit_every in [1, 5] {
+                let result = Backtest::new(30, 4).refit_every(refit_every).window(window).n_jobs(3).run(&Model::autoregressive(2), &y, None);
+                assert!(result.rmse() < 1e-6);
+                assert_eq!(result.horizon_mae().len(), 4);
+            }
+        }
+    }
+
+    #[test]
+    fn backtest_matches_forecast() {
+        let y = Array::range(0., 80., 1.).mapv(|t: f64| (t / 3.).sin() * 5. + 0.1 * t);
+        let model = Model::arima(1, 1, 1);
+        let result = Backtest::new(60, 5).step(5).window(Window::Sliding).n_jobs(2).run(&model, &y, None);
+        let mut expected = model.clone();
+        let preds = expected.forecast(&y.slice(s![15..75]).to_owned(), 5, None, None);
+        assert_eq!(result.forecasts.row(3), preds);
+    }
+
+    #[test]
+    fn backtest_benchmark() {
+        // a series repeating every 4 observations is forecast exactly by the seasonal naive method
+        let y = Array::range(0., 40., 1.).mapv(|t: f64| (t % 4.) * 3.);
+        let result = Backtest::new(8, 6).step(3).refit_every(2).run(&SeasonalNaive::new(4), &y, None);
+        assert_eq!(result.mae(), 0.);
+    }
+
+    #[test]
+    fn backtest_window_parse() {
+        assert_eq!("sliding".parse::<Window>(), Ok(Window::Sliding));
+        assert!("rolling".parse::<Window>().is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! [Simple benchmark forecasters](https://otexts.com/fpp3/simple-methods.html) to compare models against.
+//! They ignore exogenous variables, and missing values (NaN) in y are skipped.
+//! Forecast distributions are normal with the variances of fpp3, from the variance of the in-sample residuals.
+use numpy::ndarray::{Array, Array1, Array2};
+use pyo3::pyclass;
+use crate::forecaster::Forecaster;
+use crate::random::{Rng, sample_paths};
+
+
+/// every forecast is the last observation
+#[derive(PartialEq, Clone, Debug, Default)]
+#[pyclass(name = "Naive", module = "arima")]
+pub struct Naive {
+    // endog_fit: time-series
+    // sigma2: variance of the residuals
+    endog_fit: Option<Array1<f64>>,
+    sigma2: Option<f64>
+}
+
+/// every forecast is the last observation of the same season, s periods before
+#[derive(PartialEq, Clone, Debug)]
+#[pyclass(name = "SeasonalNaive", module = "arima")]
+pub struct SeasonalNaive {
+    // s: seasonal period
+    // endog_fit: time-series
+    // sigma2: variance of the residuals
+    s: usize,
+    endog_fit: Option<Array1<f64>>,
+    sigma2: Option<f64>
+}
+
+/// the last observation plus h times the average change between the first and last observations
+#[derive(PartialEq, Clone, Debug, Default)]
+#[pyclass(name = "Drift", module = "arima")]
+pub struct Drift {
+    // endog_fit: time-series
+    // drift: average change of y used for fitting
+    // sigma2: variance of the residuals
+    // nobs: number of observations
+    endog_fit: Option<Array1<f64>>,
+    drift: Option<f64>,
+    sigma2: Option<f64>,
+    nobs: usize
+}
+
+/// every forecast is the mean of the observations
+#[derive(PartialEq, Clone, Debug, Default)]
+#[pyclass(name = "Mean", module = "arima")]
+pub struct Mean {
+    // mean: of y used for fitting
+    // sigma2: variance of the residuals
+    // nobs: number of observations
+    mean: Option<f64>,
+    sigma2: Option<f64>,
+    nobs: usize
+}
+
+impl Naive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SeasonalNaive {
+    /// - s: seasonal period, 1 for the naive forecast
+    pub fn new(s: usize) -> Self {
+        if s == 0 {
+            panic!("s must be at least 1.");
+        }
+        Self {s, endog_fit: None, sigma2: None}
+    }
+}
+
+impl Drift {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Mean {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// observed (index, value) pairs of y
+fn observed(y: &Array1<f64>) -> impl DoubleEndedIterator<Item = (usize, f64)> + '_ {
+    y.iter().copied().enumerate().filter(|(_, v)| !v.is_nan())
+}
+
+/// mean of the squared residuals that are not NaN, with dof_lost parameters estimated
+fn residual_variance(residuals: impl Iterator<Item = f64>, dof_lost: usize) -> f64 {
+    let (sum, count) = residuals.filter(|e| !e.is_nan()).fold((0., 0_usize), |(sum, count), e| (sum + e * e, count + 1));
+    sum / count.saturating_sub(dof_lost).max(1) as f64
+}
+
+/// (index, value) of the first and last observations
+fn first_last(y: &Array1<f64>) -> ((usize, f64), (usize, f64)) {
+    (observed(y).next().unwrap(), observed(y).next_back().unwrap())
+}
+
+/// adds loading_k δ to each path with δ drawn from N(0, variance) once per path, for the error of an estimated parameter
+fn add_estimation_error(paths: &mut Array2<f64>, loadings: &Array1<f64>, variance: f64, rng: &mut Rng) {
+    let sd = variance.sqrt();
+    for mut path in paths.rows_mut() {
+        path.scaled_add(sd * rng.normal(), loadings);
+    }
+}
+
+fn check_observed(y: &Array1<f64>) {
+    if observed(y).next().is_none() {
+        panic!("y should have at least one observation that isn't missing (NaN).");
+    }
+}
+
+impl Forecaster for Naive {
+    fn fit(&mut self, y: &Array1<f64>, _x: Option<&Array2<f64>>) {
+        check_observed(y);
+        self.sigma2 = Some(residual_variance((1..y.len()).map(|t| y[t] - y[t - 1]), 0));
+        self.endog_fit = Some(y.to_owned());
+    }
+
+    fn predict(&self, h: usize, _x: Option<&Array2<f64>>) -> Array1<f64> {
+        let y = self.endog_fit.as_ref().expect("Naive must be fit before predict");
+        Array::from_elem(h, observed(y).next_back().unwrap().1)
+    }
+
+    /// random walks from the last observation, with variance σ² h
+    fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64> {
+        sample_paths(&self.predict(h, x), &Array::ones(h), self.sigma2.unwrap(), n, rng)
+    }
+
+    fn clone_box(&self) -> Box<dyn Forecaster> {
+        Box::new(self.clone())
+    }
+}
+
+impl Forecaster for SeasonalNaive {
+    fn fit(&mut self, y: &Array1<f64>, _x: Option<&Array2<f64>>) {
+        if y.len() < self.s {
+            panic!("y is length: {}. It should be at least one season long: {}.", y.len(), self.s);
+        }
+        check_observed(y);
+        self.sigma2 = Some(residual_variance((self.s..y.len()).map(|t| y[t] - y[t - self.s]), 0));
+        self.endog_fit = Some(y.to_owned());
+    }
+
+    /// forecasts of missing observations are the last observation of the same season before them
+    fn predict(&self, h: usize, _x: Option<&Array2<f64>>) -> Array1<f64> {
+        let y = self.endog_fit.as_ref().expect("SeasonalNaive must be fit before predict");
+        let n = y.len();
+        Array::from_iter((0..h).map(|i| {
+            // the same season in the last full season of y, then earlier seasons
+            let t = n - self.s + i % self.s;
+            (0..=t / self.s).map(|k| y[t - k * self.s]).find(|v| !v.is_nan()).unwrap_or(f64::NAN)
+        }))
+    }
+
+    /// a random walk for each season, with variance σ² (k + 1) where k is the number of full seasons before h
+    fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64> {
+        let psi = Array::from_iter((0..h).map(|j| (j % self.s == 0) as u8 as f64));
+        sample_paths(&self.predict(h, x), &psi, self.sigma2.unwrap(), n, rng)
+    }
+
+    fn clone_box(&self) -> Box<dyn Forecaster> {
+        Box::new(self.clone())
+    }
+}
+
+impl Drift {
+    /// average change between the first and last observations
+    fn drift(y: &Array1<f64>) -> f64 {
+        let ((first_t, first), (last_t, last)) = first_last(y);
+        (last - first) / (last_t - first_t) as f64
+    }
+}
+
+impl Forecaster for Drift {
+    fn fit(&mut self, y: &Array1<f64>, _x: Option<&Array2<f64>>) {
+        if observed(y).count() < 2 {
+            panic!("y should have at least two observations that aren't missing (NaN) to estimate the drift.");
+        }
+        let drift = Self::drift(y);
+        self.sigma2 = Some(residual_variance((1..y.len()).map(|t| y[t] - y[t - 1] - drift), 1));
+        self.endog_fit = Some(y.to_owned());
+        self.drift = Some(drift);
+        self.nobs = observed(y).count();
+    }
+
+    fn predict(&self, h: usize, _x: Option<&Array2<f64>>) -> Array1<f64> {
+        let y = self.endog_fit.as_ref().expect("Drift must be fit before predict");
+        let (drift, (last_t, last)) = (self.drift.unwrap(), first_last(y).1);
+        // steps from the last observation, which may be before the end of y
+        let steps = y.len() - last_t;
+        Array::from_iter((0..h).map(|i| last + (steps + i) as f64 * drift))
+    }
+
+    /// random walks from the last observation with a drift drawn for each path, with variance σ² h (1 + h / T)
+    /// for T observations, which includes the uncertainty of the drift
+    fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64> {
+        let sigma2 = self.sigma2.unwrap();
+        let mut paths = sample_paths(&self.predict(h, x), &Array::ones(h), sigma2, n, rng);
+        add_estimation_error(&mut paths, &Array::range(1., h as f64 + 1., 1.), sigma2 / self.nobs as f64, rng);
+        paths
+    }
+
+    fn clone_box(&self) -> Box<dyn Forecaster> {
+        Box::new(self.clone())
+    }
+
+    /// forecasts from the last observation of y with the drift and σ² estimated by fit
+    fn apply(&self, y: &Array1<f64>, _x: Option<&Array2<f64>>) -> Box<dyn Forecaster> {
+        if self.drift.is_none() {
+            panic!("Drift must be fit before apply");
+        }
+        check_observed(y);
+        Box::new(Self {endog_fit: Some(y.to_owned()), ..self.clone()})
+    }
+}
+
+impl Forecaster for Mean {
+    fn fit(&mut self, y: &Array1<f64>, _x: Option<&Array2<f64>>) {
+        check_observed(y);
+        let (sum, count) = observed(y).fold((0., 0), |(sum, count), (_, v)| (sum + v, count + 1));
+        let mean = sum / count as f64;
+        self.sigma2 = Some(residual_variance(y.iter().map(|v| v - mean), 1));
+        self.mean = Some(mean);
+        self.nobs = count;
+    }
+
+    fn predict(&self, h: usize, _x: Option<&Array2<f64>>) -> Array1<f64> {
+        Array::from_elem(h, self.mean.expect("Mean must be fit before predict"))
+    }
+
+    /// independent errors around a mean drawn for each path, with variance σ² (1 + 1 / T) for T observations
+    fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64> {
+        let sigma2 = self.sigma2.unwrap();
+        let psi = Array::from_iter((0..h).map(|j| (j == 0) as u8 as f64));
+        let mut paths = sample_paths(&self.predict(h, x), &psi, sigma2, n, rng);
+        add_estimation_error(&mut paths, &Array::ones(h), sigma2 / self.nobs as f64, rng);
+        paths
+    }
+
+    fn clone_box(&self) -> Box<dyn Forecaster> {
+        Box::new(self.clone())
+    }
+
+    /// the mean and σ² estimated by fit, whatever the values of y
+    fn apply(&self, _y: &Array1<f64>, _x: Option<&Array2<f64>>) -> Box<dyn Forecaster> {
+        if self.mean.is_none() {
+            panic!("Mean must be fit before apply");
+        }
+        self.clone_box()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::{arr1, arr2};
+
+    #[test]
+    fn benchmarks_naive() {
+        let y = arr1(&[1., 4., 2., f64::NAN]);
+        assert_eq!(Naive::new().forecast(&y, 2, None, None), arr1(&[2., 2.]));
+        assert_eq!(Mean::new().forecast(&y, 2, None, None), arr1(&[7. / 3., 7. / 3.]));
+    }
+
+    #[test]
+    fn benchmarks_seasonal_naive() {
+        let y = arr1(&[1., 2., 3., 4., f64::NAN, 6.]);
+        let mut model = SeasonalNaive::new(3);
+        // the missing 5th observation is replaced by the same season before it
+        assert_eq!(model.forecast(&y, 5, None, None), arr1(&[4., 2., 6., 4., 2.]));
+        assert_eq!(SeasonalNaive::new(1).forecast(&y, 2, None, None), arr1(&[6., 6.]));
+    }
+
+    #[test]
+    fn benchmarks_drift() {
+        let y = arr1(&[2., 5., 4., 8., f64::NAN]);
+        // drift (8 - 2) / 3 from the last observation at t = 3
+        assert_eq!(Drift::new().forecast(&y, 3, None, None), arr1(&[12., 14., 16.]));
+    }
+
+    #[test]
+    fn benchmarks_sample() {
+        // naive residuals 2, -2, 2, -2 and seasonal (s = 2) residuals 0, 0, 0
+        let y = arr1(&[1., 3., 1., 3., 1.]);
+        let mut rng = Rng::new(5);
+        let mut naive = Naive::new();
+        naive.fit(&y, None);
+        let draws = naive.sample(2, None, 20_000, &mut rng);
+        let variance = draws.var_axis(numpy::ndarray::Axis(0), 0.);
+        assert!((variance[0] / 4. - 1.).abs() < 0.05 && (variance[1] / 8. - 1.).abs() < 0.05);
+
+        let mut seasonal = SeasonalNaive::new(2);
+        seasonal.fit(&y, None);
+        assert_eq!(seasonal.sample(3, None, 2, &mut rng), arr2(&[[3., 1., 3.], [3., 1., 3.]]));
+    }
+
+    #[test]
+    fn benchmarks_apply() {
+        // estimated parameters are kept, the naive forecasts follow the new series
+        let mut model = Mean::new();
+        model.fit(&arr1(&[1., 2.]), None);
+        assert_eq!(model.apply(&arr1(&[5., 7.]), None).predict(1, None), arr1(&[1.5]));
+        assert_eq!(model.clone_box().predict(1, None), arr1(&[1.5]));
+
+        let mut model = Drift::new();
+        model.fit(&arr1(&[1., 2., 3.]), None);
+        assert_eq!(model.apply(&arr1(&[10., 20., f64::NAN]), None).predict(2, None), arr1(&[22., 23.]));
+
+        let mut model = Naive::new();
+        model.fit(&arr1(&[1., 2.]), None);
+        assert_eq!(model.apply(&arr1(&[5., 7.]), None).predict(1, None), arr1(&[7.]));
+    }
+}
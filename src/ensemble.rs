@@ -0,0 +1,356 @@
+use std::str::FromStr;
+use numpy::ndarray::{Array, Array1, Array2, Axis, concatenate, s};
+use pyo3::pyclass;
+use crate::backtest::Backtest;
+use crate::forecaster::Forecaster;
+use crate::metrics;
+use crate::random::Rng;
+
+
+/// maximum number of projected gradient steps for stacking weights
+const STACKING_ITERATIONS: usize = 100_000;
+/// change of the stacking weights below which they have converged
+const TOLERANCE: f64 = 1e-12;
+
+/// how the weights of an ensemble are chosen
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Weighting {
+    // the same weight for every forecaster
+    Equal,
+    // proportional to the inverse of the mean squared one-step-ahead error on the validation observations
+    InverseError,
+    // non-negative, summing to one and minimising the squared error of the combined one-step-ahead forecasts
+    // on the validation observations
+    Stacking
+}
+
+impl FromStr for Weighting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "equal" => Ok(Weighting::Equal),
+            "inverse-error" => Ok(Weighting::InverseError),
+            "stacking" => Ok(Weighting::Stacking),
+            _ => Err(format!("Unknown weighting: {}. It should be one of: equal, inverse-error, stacking.", s))
+        }
+    }
+}
+
+/// [Forecast combination](https://otexts.com/fpp3/combinations.html) of several forecasters, e.g. SARIMA
+/// models with different orders. The point forecast is the weighted average of their forecasts and the
+/// forecast distribution the mixture of theirs, sampled by drawing each path from a forecaster chosen by weight.
+///
+/// ```ignore
+/// let forecasters: Vec<Box<dyn Forecaster>> = vec![Box::new(Model::arima(1, 1, 1)), Box::new(Model::arima(0, 1, 2))];
+/// let mut ensemble = Ensemble::new(forecasters, Weighting::InverseError).validation(28);
+/// ensemble.fit(&y, None);
+/// let (lower, upper) = ensemble.predict_interval(14, None, 0.95, 1000);
+/// ```
+#[pyclass(name = "Ensemble", module = "arima")]
+pub struct Ensemble {
+    // forecasters: combined forecasters
+    // weighting: how weights are chosen
+    // validation: last observations of y forecast one step ahead, with the forecasters fit before them, to choose weights
+    // seed: of the random numbers for predict_interval
+    // weights: of each forecaster, chosen by fit
+    forecasters: Vec<Box<dyn Forecaster>>,
+    weighting: Weighting,
+    validation: usize,
+    seed: u64,
+    weights: Option<Array1<f64>>
+}
+
+impl Clone for Ensemble {
+    fn clone(&self) -> Self {
+        Self {
+            forecasters: self.forecasters.iter().map(|f| f.clone_box()).collect(),
+            weighting: self.weighting,
+            validation: self.validation,
+            seed: self.seed,
+            weights: self.weights.clone()
+        }
+    }
+}
+
+impl Ensemble {
+    pub fn new(forecasters: Vec<Box<dyn Forecaster>>, weighting: Weighting) -> Self {
+        if forecasters.is_empty() {
+            panic!("An ensemble needs at least one forecaster.");
+        }
+        Self {forecasters, weighting, validation: 0, seed: 0, weights: None}
+    }
+
+    /// number of last observations used to choose inverse-error or stacking weights
+    pub fn validation(mut self, validation: usize) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// weights of each forecaster, available once fit
+    pub fn weights(&self) -> &Array1<f64> {
+        self.weights.as_ref().expect("Ensemble must be fit before weights")
+    }
+
+    /// - h: horizons to forecast
+    /// - x: future exongenous variables, same length as h
+    /// - level: coverage of the interval, e.g. 0.95
+    /// - n_paths: number of paths simulated from the mixture
+    ///
+    /// returns the lower and upper bounds of the prediction intervals for h horizons, as quantiles of the mixture
+    pub fn predict_interval(&self, h: usize, x: Option<&Array2<f64>>, level: f64, n_paths: usize) -> (Array1<f64>, Array1<f64>) {
+        if level <= 0. || level >= 1. {
+            panic!("level should be between 0 and 1, got {}.", level);
+        }
+        let draws = self.sample(h, x, n_paths, &mut Rng::new(self.seed));
+        let lower = draws.map_axis(Axis(0), |d| quantile(d.to_vec(), 0.5 - level / 2.));
+        let upper = draws.map_axis(Axis(0), |d| quantile(d.to_vec(), 0.5 + level / 2.));
+        (lower, upper)
+    }
+
+    /// one-step-ahead forecasts of the validation observations, one column per forecaster
+    fn validation_forecasts(&self, y: &Array1<f64>, x: Option<&Array2<f64>>) -> Array2<f64> {
+        let n = y.len();
+        if self.validation == 0 || self.validation >= n {
+            panic!("validation must be between 1 and the length of y ({}) to choose {:?} weights, got {}.", n, self.weighting, self.validation);
+        }
+        let backtest = Backtest::new(n - self.validation, 1).refit_every(self.validation).n_jobs(1);
+        let columns: Vec<Array2<f64>> = self.forecasters
+            .iter()
+            .map(|f| backtest.run(f.as_ref(), y, x).forecasts)
+            .collect();
+        let views: Vec<_> = columns.iter().map(|c| c.view()).collect();
+        concatenate(Axis(1), &views).unwrap()
+    }
+}
+
+/// weights w ≥ 0 with Σ w = 1 minimising |actual - forecasts w|², by accelerated projected gradient descent
+/// ([FISTA](https://doi.org/10.1137/080716542)) since forecasts are usually close to collinear
+fn stacking_weights(actual: &Array1<f64>, forecasts: &Array2<f64>) -> Array1<f64> {
+    let k = forecasts.shape()[1];
+    let gram = forecasts.t().dot(forecasts);
+    let cross = forecasts.t().dot(actual);
+
+    // largest eigenvalue of the Gram matrix by power iteration
+    let mut v = Array::from_elem(k, 1.);
+    let mut eigenvalue = 0.;
+    for _ in 0..100 {
+        let gv = gram.dot(&v);
+        eigenvalue = gv.dot(&gv).sqrt();
+        if eigenvalue == 0. {
+            break;
+        }
+        v = gv / eigenvalue;
+    }
+    let step = 1. / eigenvalue.max(f64::MIN_POSITIVE);
+
+    let mut w = Array::from_elem(k, 1. / k as f64);
+    let (mut z, mut t): (Array1<f64>, f64) = (w.clone(), 1.);
+    for _ in 0..STACKING_ITERATIONS {
+        let gradient = gram.dot(&z) - &cross;
+        let w_next = project_simplex(&(&z - &(gradient * step)));
+        let t_next = (1. + (1. + 4. * t * t).sqrt()) / 2.;
+        z = &w_next + &((&w_next - &w) * ((t - 1.) / t_next));
+        let change = (&w_next - &w).mapv(f64::abs).sum();
+        (w, t) = (w_next, t_next);
+        if change < TOLERANCE {
+            break;
+        }
+    }
+    w
+}
+
+/// Euclidean projection onto {w ≥ 0, Σ w = 1}, [Duchi et al. (2008)](https://doi.org/10.1145/1390156.1390191)
+fn project_simplex(v: &Array1<f64>) -> Array1<f64> {
+    let mut sorted = v.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    let mut cumulative = 0.;
+    let mut theta = 0.;
+    for (i, u) in sorted.iter().enumerate() {
+        cumulative += u;
+        let t = (cumulative - 1.) / (i + 1) as f64;
+        if u - t > 0. {
+            theta = t;
+        }
+    }
+    v.mapv(|v| (v - theta).max(0.))
+}
+
+/// p quantile of the values that are not NaN, interpolating linearly between order statistics
+fn quantile(mut values: Vec<f64>, p: f64) -> f64 {
+    values.retain(|v| !v.is_nan());
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    values.sort_by(f64::total_cmp);
+    let position = p * (values.len() - 1) as f64;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+    values[below] + (position - below as f64) * (values[above] - values[below])
+}
+
+impl Forecaster for Ensemble {
+    /// chooses the weights, then fits every forecaster to y
+    fn fit(&mut self, y: &Array1<f64>, x: Option<&Array2<f64>>) {
+        let k = self.forecasters.len();
+        let weights = if self.weighting == Weighting::Equal {
+            Array::from_elem(k, 1. / k as f64)
+        } else {
+            let forecasts = self.validation_forecasts(y, x);
+            let actual = y.slice(s![y.len() - self.validation..]).to_owned();
+            match self.weighting {
+                Weighting::InverseError => {
+                    // forecasters without a validation forecast get no weight
+                    let inverse = Array::from_iter(forecasts.columns().into_iter().map(|f| {
+                        let rmse = metrics::rmse(&actual, &f.to_owned());
+                        if rmse.is_nan() {0.} else {1. / rmse.powi(2)}
+                    }));
+                    if inverse.sum() == 0. {
+                        panic!("No forecaster has a validation forecast of an observation that isn't missing (NaN) to weight it by.");
+                    }
+                    // forecasters without errors share all the weight
+                    let inverse = if inverse.iter().any(|w| w.is_infinite()) {inverse.mapv(|w| w.is_infinite() as u8 as f64)} else {inverse};
+                    &inverse / inverse.sum()
+                },
+                _ => {
+                    let complete: Vec<usize> = (0..actual.len())
+                        .filter(|&i| !actual[i].is_nan() && forecasts.row(i).iter().all(|f| !f.is_nan()))
+                        .collect();
+                    stacking_weights(&actual.select(Axis(0), &complete), &forecasts.select(Axis(0), &complete))
+                }
+            }
+        };
+        for forecaster in self.forecasters.iter_mut() {
+            forecaster.fit(y, x);
+        }
+        self.weights = Some(weights);
+    }
+
+    /// weighted average of the forecasts
+    fn predict(&self, h: usize, x: Option<&Array2<f64>>) -> Array1<f64> {
+        let weights = self.weights();
+        self.forecasters
+            .iter()
+            .zip(weights.iter())
+            .filter(|(_, &w)| w > 0.)
+            .fold(Array::zeros(h), |acc, (f, w)| acc + f.predict(h, x) * *w)
+    }
+
+    /// paths from the mixture, each simulated by a forecaster chosen with probability equal to its weight
+    fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64> {
+        let weights = self.weights();
+        let mut counts = vec![0; self.forecasters.len()];
+        for _ in 0..n {
+            counts[rng.choose(weights)] += 1;
+        }
+        let draws: Vec<Array2<f64>> = self.forecasters
+            .iter()
+            .zip(counts)
+            .map(|(f, count)| if count > 0 {f.sample(h, x, count, rng)} else {Array::zeros((0, h))})
+            .collect();
+        let views: Vec<_> = draws.iter().map(|d| d.view()).collect();
+        concatenate(Axis(0), &views).unwrap()
+    }
+
+    fn clone_box(&self) -> Box<dyn Forecaster> {
+        Box::new(self.clone())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarks::{Drift, Mean, Naive};
+    use crate::model::Model;
+    use numpy::ndarray::arr1;
+
+    #[test]
+    fn ensemble_equal() {
+        let y = arr1(&[1., 2., 3., 4., 5.]);
+        let mut ensemble = Ensemble::new(vec![Box::new(Naive::new()), Box::new(Mean::new())], Weighting::Equal);
+        ensemble.fit(&y, None);
+        assert_eq!(ensemble.weights(), arr1(&[0.5, 0.5]));
+        assert_eq!(ensemble.predict(2, None), arr1(&[4., 4.]));
+    }
+
+    #[test]
+    fn ensemble_inverse_error_and_stacking() {
+        // a line is forecast exactly by the drift method, so it gets all the weight
+        let y = Array::range(0., 40., 1.).mapv(|t: f64| 3. + 0.5 * t);
+        for weighting in [Weighting::InverseError, Weighting::Stacking] {
+            let forecasters: Vec<Box<dyn Forecaster>> = vec![Box::new(Naive::new()), Box::new(Drift::new()), Box::new(Mean::new())];
+            let mut ensemble = Ensemble::new(forecasters, weighting).validation(10);
+            ensemble.fit(&y, None);
+            let weights = ensemble.weights();
+            assert!((weights[1] - 1.).abs() < 1e-6, "{:?}: {}", weighting, weights);
+            assert!((ensemble.predict(1, None)[0] - 23.).abs() < 1e-6);
+        }
+    }
+
+    /// forecasts nothing, like a forecaster failing on the validation observations
+    #[derive(Clone)]
+    struct Unavailable;
+
+    impl Forecaster for Unavailable {
+        fn fit(&mut self, _y: &Array1<f64>, _x: Option<&Array2<f64>>) {}
+
+        fn predict(&self, h: usize, _x: Option<&Array2<f64>>) -> Array1<f64> {
+            Array::from_elem(h, f64::NAN)
+        }
+
+        fn sample(&self, h: usize, _x: Option<&Array2<f64>>, n: usize, _rng: &mut Rng) -> Array2<f64> {
+            Array::from_elem((n, h), f64::NAN)
+        }
+
+        fn clone_box(&self) -> Box<dyn Forecaster> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn ensemble_inverse_error_unavailable() {
+        let y = arr1(&[1., 3., 2., 4., 3., 5.]);
+        let mut ensemble = Ensemble::new(vec![Box::new(Naive::new()), Box::new(Unavailable)], Weighting::InverseError).validation(3);
+        ensemble.fit(&y, None);
+        assert_eq!(ensemble.weights(), arr1(&[1., 0.]));
+    }
+
+    #[test]
+    #[should_panic(expected = "No forecaster has a validation forecast")]
+    fn ensemble_inverse_error_none_available() {
+        let mut ensemble = Ensemble::new(vec![Box::new(Unavailable)], Weighting::InverseError).validation(3);
+        ensemble.fit(&arr1(&[1., 3., 2., 4., 3., 5.]), None);
+    }
+
+    #[test]
+    fn ensemble_stacking_weights() {
+        // actual = 0.3 a + 0.7 b
+        let forecasts = Array2::from_shape_fn((20, 2), |(i, j)| if j == 0 {i as f64} else {(i * i) as f64 / 10.});
+        let actual = forecasts.column(0).mapv(|v| 0.3 * v) + forecasts.column(1).mapv(|v| 0.7 * v);
+        let w = stacking_weights(&actual, &forecasts);
+        assert!((w[0] - 0.3).abs() < 1e-3 && (w[1] - 0.7).abs() < 1e-3);
+        assert_eq!(project_simplex(&arr1(&[2., 0.])), arr1(&[1., 0.]));
+    }
+
+    #[test]
+    fn ensemble_interval() {
+        let mut rng = Rng::new(6);
+        let y = Array::from_iter((0..120).map(|_| 10. + rng.normal()));
+        let forecasters: Vec<Box<dyn Forecaster>> = vec![Box::new(Model::arma(1, 0)), Box::new(Mean::new())];
+        let mut ensemble = Ensemble::new(forecasters, Weighting::Equal).seed(1);
+        ensemble.fit(&y, None);
+
+        let (lower, upper) = ensemble.predict_interval(3, None, 0.9, 4000);
+        let preds = ensemble.predict(3, None);
+        assert!((0..3).all(|j| lower[j] < preds[j] && preds[j] < upper[j]));
+        // both components are close to N(10, 1), so is the mixture
+        assert!((0..3).all(|j| ((upper[j] - lower[j]) / (2. * 1.645) - 1.).abs() < 0.15));
+        assert_eq!(ensemble.predict_interval(3, None, 0.9, 4000), (lower, upper));
+        assert_eq!(quantile(vec![3., 1., 2., f64::NAN], 0.25), 1.5);
+    }
+}
@@ -0,0 +1,69 @@
+use numpy::ndarray::{Array1, Array2};
+use crate::model::Model;
+use crate::random::Rng;
+
+
+/// Common interface of `Model` and the benchmark forecasters, so that any of them can be backtested or ensembled.
+///
+/// ```ignore
+/// let forecasters: Vec<Box<dyn Forecaster>> = vec![Box::new(Model::arima(1, 1, 1)), Box::new(SeasonalNaive::new(7))];
+/// for forecaster in forecasters.iter() {
+///     Backtest::new(100, 14).run(forecaster.as_ref(), &y, None).rmse();
+/// }
+/// ```
+pub trait Forecaster: Send + Sync {
+    /// - y: timeseries
+    /// - x: exogenous variables, same length as y, ignored by forecasters without them
+    fn fit(&mut self, y: &Array1<f64>, x: Option<&Array2<f64>>);
+
+    /// - h: horizons to forecast
+    /// - x: future exogenous variables, same length as h
+    fn predict(&self, h: usize, x: Option<&Array2<f64>>) -> Array1<f64>;
+
+    /// - h: horizons to forecast
+    /// - x: future exogenous variables, same length as h
+    /// - n: number of draws
+    ///
+    /// returns n simulated future paths, one row per path, whose values at each horizon are draws
+    /// from its forecast distribution
+    fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64>;
+
+    /// fit followed by predict
+    fn forecast(&mut self, y: &Array1<f64>, h: usize, x: Option<&Array2<f64>>, x_future: Option<&Array2<f64>>) -> Array1<f64> {
+        self.fit(y, x);
+        self.predict(h, x_future)
+    }
+
+    /// a copy with the same specification and fitted state
+    fn clone_box(&self) -> Box<dyn Forecaster>;
+
+    /// a forecaster for another series with the parameters estimated by fit.
+    /// Forecasters without estimated parameters, like the naive ones, are simply fit to it.
+    fn apply(&self, y: &Array1<f64>, x: Option<&Array2<f64>>) -> Box<dyn Forecaster> {
+        let mut forecaster = self.clone_box();
+        forecaster.fit(y, x);
+        forecaster
+    }
+}
+
+impl Forecaster for Model {
+    fn fit(&mut self, y: &Array1<f64>, x: Option<&Array2<f64>>) {
+        Model::fit(self, y, x)
+    }
+
+    fn predict(&self, h: usize, x: Option<&Array2<f64>>) -> Array1<f64> {
+        Model::predict(self, h, x)
+    }
+
+    fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64> {
+        Model::sample(self, h, x, n, rng)
+    }
+
+    fn clone_box(&self) -> Box<dyn Forecaster> {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, y: &Array1<f64>, x: Option<&Array2<f64>>) -> Box<dyn Forecaster> {
+        Box::new(Model::apply(self, y, x))
+    }
+}
@@ -0,0 +1,155 @@
+use std::f64::consts::PI;
+use numpy::ndarray::{Array, Array1, Array2, Axis, concatenate};
+use crate::model::Model;
+
+
+/// [Fourier terms](https://otexts.com/fpp3/useful-predictors.html#fourier-series) for long or multiple seasonal
+/// periods, to be used as exogenous variables instead of seasonal lags and differences.
+///
+/// ```ignore
+/// let fourier = Fourier::new(vec![7., 365.25], vec![3, 10]);
+/// model.fit(&y, Some(&fourier.terms(0, y.len())));
+/// model.predict(h, Some(&fourier.terms(y.len(), h)));
+/// ```
+#[derive(PartialEq, Clone, Debug)]
+pub struct Fourier {
+    periods: Vec<f64>,
+    k: Vec<usize>
+}
+
+impl Fourier {
+    /// - periods: seasonal periods, which don't need to be integers, e.g. 365.25
+    /// - k: number of sine and cosine pairs for each period, at most period / 2
+    pub fn new(periods: Vec<f64>, k: Vec<usize>) -> Self {
+        if periods.len() != k.len() {
+            panic!("Got {} periods but {} values of k.", periods.len(), k.len());
+        }
+        for (period, k) in periods.iter().zip(k.iter()) {
+            if *period <= 1. {
+                panic!("Periods must be greater than 1, got {}.", period);
+            }
+            if *k as f64 > period / 2. {
+                panic!("k must be at most period / 2, got k = {} for period {}.", k, period);
+            }
+        }
+        Self {periods, k}
+    }
+
+    /// number of sine and cosine pairs for each period
+    pub fn k(&self) -> &[usize] {
+        &self.k
+    }
+
+    /// number of columns of the terms
+    pub fn n_cols(&self) -> usize {
+        (0..self.periods.len()).map(|i| self.harmonics(i).iter().map(|(_, has_sin)| 1 + *has_sin as usize).sum::<usize>()).sum()
+    }
+
+    /// (k, whether the sine term is included) for each harmonic of period i.
+    /// The sine term of k = period / 2 is zero at integer times so it is dropped.
+    fn harmonics(&self, i: usize) -> Vec<(usize, bool)> {
+        (1..=self.k[i]).map(|k| (k, 2. * k as f64 != self.periods[i])).collect()
+    }
+
+    /// sin(2π k t / period) and cos(2π k t / period) for each period and k, for t = start, ..., start + n - 1.
+    /// Use start 0 with the length of y for fitting and start at the length of y for predicting.
+    pub fn terms(&self, start: usize, n: usize) -> Array2<f64> {
+        let t = Array::range(start as f64, (start + n) as f64, 1.);
+        let mut columns: Vec<Array1<f64>> = Vec::new();
+        for (i, period) in self.periods.iter().enumerate() {
+            for (k, has_sin) in self.harmonics(i) {
+                let angle = t.mapv(|t| 2. * PI * k as f64 * t / period);
+                if has_sin {
+                    columns.push(angle.mapv(f64::sin));
+                }
+                columns.push(angle.mapv(f64::cos));
+            }
+        }
+        let views: Vec<_> = columns.iter().map(|c| c.view().insert_axis(Axis(1))).collect();
+        concatenate(Axis(1), &views).unwrap_or_else(|_| Array::zeros((n, 0)))
+    }
+
+    /// Fourier terms with k chosen for each period by minimising the AICc of the model fitted with them,
+    /// searching all combinations of k from 1 to max_k.
+    /// - model: specification to fit, usually without seasonal terms
+    /// - y: timeseries
+    /// - x: other exogenous variables, same length as y, placed before the Fourier terms
+    /// - periods: seasonal periods
+    /// - max_k: largest k for each period
+    pub fn select_k(model: &Model, y: &Array1<f64>, x: Option<&Array2<f64>>, periods: Vec<f64>, max_k: Vec<usize>) -> Self {
+        if periods.len() != max_k.len() {
+            panic!("Got {} periods but {} values of max_k.", periods.len(), max_k.len());
+        }
+        let x = x.map_or_else(|| Array::zeros((y.len(), 0)), |x| x.to_owned());
+
+        let mut candidates: Vec<Vec<usize>> = vec![Vec::new()];
+        for max_k in max_k.iter() {
+            candidates = candidates
+                .into_iter()
+                .flat_map(|k| (1..=*max_k).map(move |k_i| [k.clone(), vec![k_i]].concat()))
+                .collect();
+        }
+
+        candidates
+            .into_iter()
+            .map(|k| {
+                let fourier = Self::new(periods.clone(), k);
+                let x_fit = concatenate![Axis(1), x.view(), fourier.terms(0, y.len()).view()];
+                let mut model = model.clone();
+                model.fit(y, Some(&x_fit));
+                (fourier, model.aicc())
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("At least one period is needed to select k.")
+            .0
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ModelBuilder, Trend};
+    use crate::random::Rng;
+    use numpy::ndarray::{arr1, s};
+
+    #[test]
+    fn fourier_terms() {
+        let fourier = Fourier::new(vec![4.], vec![2]);
+        let terms = fourier.terms(1, 4).mapv(|v| (1e6 * v).round() / 1e6 + 0.);
+        // sin and cos of k = 1, cos only for k = 2 = period / 2
+        assert_eq!(fourier.n_cols(), 3);
+        assert_eq!(terms.column(0), arr1(&[1., 0., -1., 0.]));
+        assert_eq!(terms.column(1), arr1(&[0., -1., 0., 1.]));
+        assert_eq!(terms.column(2), arr1(&[-1., 1., -1., 1.]));
+    }
+
+    #[test]
+    fn fourier_non_integer_period() {
+        let fourier = Fourier::new(vec![7., 365.25], vec![1, 3]);
+        let fit = fourier.terms(0, 400);
+        let future = fourier.terms(400, 10);
+        assert_eq!(fit.shape(), &[400, 8]);
+        assert_eq!(future.slice(s![0, ..]), fourier.terms(0, 401).slice(s![400, ..]));
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be at most period / 2")]
+    fn fourier_k_too_large() {
+        Fourier::new(vec![7.], vec![4]);
+    }
+
+    #[test]
+    fn fourier_select_k() {
+        // seasonality of period 12.5 with two harmonics plus a little noise
+        let n = 200;
+        let mut rng = Rng::new(7);
+        let y = Array::from_iter((0..n).map(|t| {
+            let angle = 2. * PI * t as f64 / 12.5;
+            10. + 3. * angle.sin() + 2. * (2. * angle).cos() + 0.05 * rng.normal()
+        }));
+        let model = ModelBuilder::new().trend(Trend::C).build();
+        let fourier = Fourier::select_k(&model, &y, None, vec![12.5], vec![5]);
+        assert_eq!(fourier, Fourier::new(vec![12.5], vec![2]));
+    }
+}
@@ -12,13 +12,30 @@
 //! Check out [Wikipedia](https://en.wikipedia.org/wiki/Autoregressive_integrated_moving_average) for more information.
 //! 
 
+// pyo3 0.20 expands #[new] into an impl block nested inside a function
+#![allow(non_local_definitions)]
+
 mod model;
-pub use model::Model;
+mod fourier;
+mod backtest;
+mod benchmarks;
+mod ensemble;
+mod forecaster;
+mod random;
+pub mod metrics;
+pub use fourier::Fourier;
+pub use backtest::{Backtest, BacktestResult, Window};
+pub use benchmarks::{Drift, Mean, Naive, SeasonalNaive};
+pub use ensemble::{Ensemble, Weighting};
+pub use forecaster::Forecaster;
+pub use random::Rng;
+pub use model::{Exog, FutureErrors, Method, Missing, Model, ModelBuilder, Noise, Regression, Seasonality, Transform, Trend};
 
 use numpy::ndarray::{Array, Array2};
-use numpy::{IntoPyArray, PyArray1, PyArrayLike1, PyArrayLike2};
-use pyo3::{Python, PyResult, pymethods, pymodule};
-use pyo3::types::{PyModule, PyType};
+use numpy::{Complex64, IntoPyArray, PyArray1, PyArray2, PyArrayLike1, PyArrayLike2};
+use pyo3::{PyRef, Python, PyResult, pyfunction, pymethods, pymodule, wrap_pyfunction};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::types::{PyAny, PyDict, PyModule, PyType};
 
 fn unwrap_x(x: Option<PyArrayLike2<f64>>, default_length: usize) -> Array2<f64> {
     match x {
@@ -27,18 +44,125 @@ fn unwrap_x(x: Option<PyArrayLike2<f64>>, default_length: usize) -> Array2<f64>
     }
 }
 
+/// a single (P, D, Q, s) or a list of them, one for each seasonal period
+fn seasonal_orders(seasonal_order: &PyAny) -> PyResult<Vec<(usize, usize, usize, usize)>> {
+    match seasonal_order.extract::<(usize, usize, usize, usize)>() {
+        Ok(seasonal_order) => Ok(vec![seasonal_order]),
+        Err(_) => seasonal_order.extract()
+    }
+}
+
+fn build(builder: ModelBuilder) -> PyResult<Model> {
+    builder.validate().map_err(PyValueError::new_err)?;
+    Ok(builder.build())
+}
+
 #[pymethods]
 impl Model {
+    #[new]
+    #[pyo3(signature = (order = (0, 0, 0), seasonal_order = None, seasonality = "multiplicative", trend = None, method = "recursive", future_errors = "zero", exog = None, regression = "armax", exog_lags = None, missing = "raise", transform = None, bias_adjust = false, enforce_stationarity = false, enforce_invertibility = false, allow_high_order_trend = false))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        order: (usize, usize, usize),
+        seasonal_order: Option<&PyAny>,
+        seasonality: &str,
+        trend: Option<&str>,
+        method: &str,
+        future_errors: &str,
+        exog: Option<&PyAny>,
+        regression: &str,
+        exog_lags: Option<Vec<Vec<usize>>>,
+        missing: &str,
+        transform: Option<&PyAny>,
+        bias_adjust: bool,
+        enforce_stationarity: bool,
+        enforce_invertibility: bool,
+        allow_high_order_trend: bool
+    ) -> PyResult<Self> {
+        let mut builder = ModelBuilder::new()
+            .order(order)
+            .seasonality(seasonality.parse().map_err(PyValueError::new_err)?)
+            .method(method.parse().map_err(PyValueError::new_err)?)
+            .future_errors(future_errors.parse().map_err(PyValueError::new_err)?)
+            .regression(regression.parse().map_err(PyValueError::new_err)?)
+            .exog_lags(exog_lags.unwrap_or_default())
+            .missing(missing.parse().map_err(PyValueError::new_err)?)
+            .bias_adjust(bias_adjust)
+            .enforce_stationarity(enforce_stationarity)
+            .enforce_invertibility(enforce_invertibility)
+            .allow_high_order_trend(allow_high_order_trend);
+        if let Some(seasonal_order) = seasonal_order {
+            builder = builder.seasonal_orders(seasonal_orders(seasonal_order)?);
+        }
+        if let Some(exog) = exog {
+            let exog = match exog.extract::<Vec<bool>>() {
+                Ok(columns) => Exog::Columns(columns),
+                Err(_) => exog.extract::<&str>()?.parse().map_err(PyValueError::new_err)?
+            };
+            builder = builder.exog(exog);
+        }
+        if let Some(transform) = transform {
+            let transform = match transform.extract::<f64>() {
+                Ok(lambda) => Transform::BoxCox(lambda),
+                Err(_) => transform.extract::<&str>()?.parse().map_err(PyValueError::new_err)?
+            };
+            builder = builder.transform(transform);
+        }
+        if let Some(trend) = trend {
+            builder = builder.trend(trend.parse().map_err(PyValueError::new_err)?);
+        }
+        build(builder)
+    }
+
     #[pyo3(name = "fit", signature = (y, x = None))]
     fn py_fit<'py>(&mut self, y: PyArrayLike1<'py, f64>, x: Option<PyArrayLike2<'py, f64>>) {
         self.fit(&y.as_array().to_owned(), Some(&unwrap_x(x, y.len())))
     }
+    #[pyo3(name = "append", signature = (y, x = None, refit = false))]
+    fn py_append<'py>(&mut self, y: PyArrayLike1<'py, f64>, x: Option<PyArrayLike2<'py, f64>>, refit: bool) {
+        self.append(&y.as_array().to_owned(), Some(&unwrap_x(x, y.len())), refit)
+    }
+
+    #[pyo3(name = "apply", signature = (y, x = None))]
+    fn py_apply<'py>(&self, y: PyArrayLike1<'py, f64>, x: Option<PyArrayLike2<'py, f64>>) -> Self {
+        self.apply(&y.as_array().to_owned(), Some(&unwrap_x(x, y.len())))
+    }
+
     #[pyo3(name = "predict", signature = (h, x = None))]
-    fn py_predict<'py>(&mut self, py: Python<'py>, h: usize, x: Option<PyArrayLike2<'py, f64>>
+    fn py_predict<'py>(&self, py: Python<'py>, h: usize, x: Option<PyArrayLike2<'py, f64>>
 ) -> &'py PyArray1<f64> {
         self.predict(h, Some(&unwrap_x(x, h))).into_pyarray(py)
     }
 
+    #[pyo3(name = "simulate", signature = (n, params, sigma = 1.0, x = None, noise = None, seed = 0, burn_in = 100))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_simulate<'py>(&self, py: Python<'py>, n: usize, params: PyArrayLike1<'py, f64>, sigma: f64, x: Option<PyArrayLike2<'py, f64>>, noise: Option<&PyAny>, seed: u64, burn_in: usize
+) -> PyResult<&'py PyArray1<f64>> {
+        let noise = match noise {
+            None => Noise::Normal,
+            Some(noise) => match noise.extract::<f64>() {
+                Ok(df) => Noise::StudentT(df),
+                Err(_) => noise.extract::<&str>()?.parse().map_err(PyValueError::new_err)?
+            }
+        };
+        let x = x.map(|x| x.as_array().to_owned());
+        Ok(self.simulate(n, &params.as_array().to_owned(), sigma, x.as_ref(), noise, seed, burn_in).into_pyarray(py))
+    }
+
+    #[pyo3(name = "predict_interval", signature = (h, x = None, level = 0.95))]
+    fn py_predict_interval<'py>(&self, py: Python<'py>, h: usize, x: Option<PyArrayLike2<'py, f64>>, level: f64
+) -> (&'py PyArray1<f64>, &'py PyArray1<f64>) {
+        let (lower, upper) = self.predict_interval(h, Some(&unwrap_x(x, h)), level);
+        (lower.into_pyarray(py), upper.into_pyarray(py))
+    }
+
+    #[pyo3(name = "predict_range", signature = (start, end, dynamic = None, x = None))]
+    fn py_predict_range<'py>(&self, py: Python<'py>, start: usize, end: usize, dynamic: Option<usize>, x: Option<PyArrayLike2<'py, f64>>
+) -> &'py PyArray1<f64> {
+        let x = x.map(|x| x.as_array().to_owned());
+        self.predict_range(start, end, dynamic, x.as_ref()).into_pyarray(py)
+    }
+
     #[pyo3(name = "forecast", signature = (y, h, x = None, x_future = None))]
     fn py_forecast<'py>(&mut self, py: Python<'py>, y: PyArrayLike1<'py, f64>, h: usize, x: Option<PyArrayLike2<'py, f64>>, x_future: Option<PyArrayLike2<'py, f64>>
 ) -> &'py PyArray1<f64> {
@@ -51,11 +175,21 @@ impl Model {
         self.fit_predict(&y.as_array().to_owned(), h, Some(&unwrap_x(x, y.len())), Some(&unwrap_x(x_future, h))).into_pyarray(py)
     }
 
+    #[pyo3(name = "fitted_values")]
+    fn py_fitted_values<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+        self.fitted_values().into_pyarray(py)
+    }
+
+    #[pyo3(name = "residuals")]
+    fn py_residuals<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+        self.residuals().into_pyarray(py)
+    }
+
     // https://pyo3.rs/v0.20.3/class#class-methods
     #[classmethod]
     #[pyo3(name = "sarima")]
-    fn py_sarima(_cls: &PyType, order: (usize, usize, usize), seasonal_order: (usize, usize, usize, usize)) -> PyResult<Self> {
-        Ok(Self::sarima(order, seasonal_order))
+    fn py_sarima(_cls: &PyType, order: (usize, usize, usize), seasonal_order: &PyAny) -> PyResult<Self> {
+        build(ModelBuilder::new().order(order).seasonal_orders(seasonal_orders(seasonal_order)?))
     }
 
     #[classmethod]
@@ -82,6 +216,69 @@ impl Model {
         Ok(Self::moving_average(q))
     }
 
+    fn __repr__(&self) -> String {
+        let fitted = if self.is_fitted() {"True"} else {"False"};
+        format!("Model({}, fitted={}, n_exog={})", self, fitted, self.n_exog())
+    }
+
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+
+    #[getter]
+    #[pyo3(name = "is_fitted")]
+    fn py_is_fitted(&self) -> bool {
+        self.is_fitted()
+    }
+
+    #[getter]
+    #[pyo3(name = "n_exog")]
+    fn py_n_exog(&self) -> usize {
+        self.n_exog()
+    }
+
+    #[getter]
+    #[pyo3(name = "coef_names")]
+    fn py_coef_names(&self) -> Vec<String> {
+        self.coef_names()
+    }
+
+    #[getter]
+    #[pyo3(name = "aic")]
+    fn py_aic(&self) -> f64 {
+        self.aic()
+    }
+
+    #[getter]
+    #[pyo3(name = "aicc")]
+    fn py_aicc(&self) -> f64 {
+        self.aicc()
+    }
+
+    #[getter]
+    #[pyo3(name = "ar_roots")]
+    fn py_ar_roots<'py>(&self, py: Python<'py>) -> &'py PyArray1<Complex64> {
+        self.ar_roots().into_pyarray(py)
+    }
+
+    #[getter]
+    #[pyo3(name = "ma_roots")]
+    fn py_ma_roots<'py>(&self, py: Python<'py>) -> &'py PyArray1<Complex64> {
+        self.ma_roots().into_pyarray(py)
+    }
+
+    #[getter]
+    #[pyo3(name = "is_stationary")]
+    fn py_is_stationary(&self) -> bool {
+        self.is_stationary()
+    }
+
+    #[getter]
+    #[pyo3(name = "is_invertible")]
+    fn py_is_invertible(&self) -> bool {
+        self.is_invertible()
+    }
+
     #[getter]
     fn coefs<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
         Ok(self.coefs.to_owned().unwrap().into_pyarray(py))
@@ -89,10 +286,267 @@ impl Model {
 }
 
 
+/// fit, predict and forecast of a benchmark forecaster, see [Forecaster]
+macro_rules! py_benchmark {
+    ($cls:ident, ($($arg:ident: $ty:ty),*), $new:block) => {
+        #[pymethods]
+        impl $cls {
+            #[new]
+            fn py_new($($arg: $ty),*) -> PyResult<Self> $new
+
+            #[pyo3(name = "fit", signature = (y, x = None))]
+            fn py_fit<'py>(&mut self, y: PyArrayLike1<'py, f64>, x: Option<PyArrayLike2<'py, f64>>) {
+                Forecaster::fit(self, &y.as_array().to_owned(), Some(&unwrap_x(x, y.len())))
+            }
+
+            #[pyo3(name = "predict", signature = (h, x = None))]
+            fn py_predict<'py>(&self, py: Python<'py>, h: usize, x: Option<PyArrayLike2<'py, f64>>) -> &'py PyArray1<f64> {
+                Forecaster::predict(self, h, Some(&unwrap_x(x, h))).into_pyarray(py)
+            }
+
+            #[pyo3(name = "forecast", signature = (y, h, x = None, x_future = None))]
+            fn py_forecast<'py>(&mut self, py: Python<'py>, y: PyArrayLike1<'py, f64>, h: usize, x: Option<PyArrayLike2<'py, f64>>, x_future: Option<PyArrayLike2<'py, f64>>
+) -> &'py PyArray1<f64> {
+                Forecaster::forecast(self, &y.as_array().to_owned(), h, Some(&unwrap_x(x, y.len())), Some(&unwrap_x(x_future, h))).into_pyarray(py)
+            }
+        }
+    };
+}
+
+py_benchmark!(Naive, (), {Ok(Naive::new())});
+py_benchmark!(Drift, (), {Ok(Drift::new())});
+py_benchmark!(Mean, (), {Ok(Mean::new())});
+py_benchmark!(SeasonalNaive, (s: usize), {
+    if s == 0 {
+        return Err(PyValueError::new_err("s must be at least 1."));
+    }
+    Ok(SeasonalNaive::new(s))
+});
+
+/// a Model or benchmark forecaster
+fn forecaster(obj: &PyAny) -> PyResult<Box<dyn Forecaster>> {
+    if let Ok(model) = obj.extract::<PyRef<Model>>() {
+        return Ok(model.clone_box());
+    }
+    if let Ok(model) = obj.extract::<PyRef<Naive>>() {
+        return Ok(model.clone_box());
+    }
+    if let Ok(model) = obj.extract::<PyRef<SeasonalNaive>>() {
+        return Ok(model.clone_box());
+    }
+    if let Ok(model) = obj.extract::<PyRef<Drift>>() {
+        return Ok(model.clone_box());
+    }
+    if let Ok(model) = obj.extract::<PyRef<Mean>>() {
+        return Ok(model.clone_box());
+    }
+    if let Ok(model) = obj.extract::<PyRef<Ensemble>>() {
+        return Ok(model.clone_box());
+    }
+    Err(PyTypeError::new_err("model should be a Model, Naive, SeasonalNaive, Drift, Mean or Ensemble."))
+}
+
+#[pymethods]
+impl Ensemble {
+    #[new]
+    #[pyo3(signature = (models, weights = "equal", validation = 0, seed = 0))]
+    fn py_new(models: Vec<&PyAny>, weights: &str, validation: usize, seed: u64) -> PyResult<Self> {
+        let weighting: Weighting = weights.parse().map_err(PyValueError::new_err)?;
+        if models.is_empty() {
+            return Err(PyValueError::new_err("An ensemble needs at least one model."));
+        }
+        if weighting != Weighting::Equal && validation == 0 {
+            return Err(PyValueError::new_err(format!("validation must be at least 1 to choose {} weights.", weights)));
+        }
+        let forecasters = models.into_iter().map(forecaster).collect::<PyResult<Vec<_>>>()?;
+        Ok(Ensemble::new(forecasters, weighting).validation(validation).seed(seed))
+    }
+
+    #[pyo3(name = "fit", signature = (y, x = None))]
+    fn py_fit<'py>(&mut self, y: PyArrayLike1<'py, f64>, x: Option<PyArrayLike2<'py, f64>>) {
+        Forecaster::fit(self, &y.as_array().to_owned(), Some(&unwrap_x(x, y.len())))
+    }
+
+    #[pyo3(name = "predict", signature = (h, x = None))]
+    fn py_predict<'py>(&self, py: Python<'py>, h: usize, x: Option<PyArrayLike2<'py, f64>>) -> &'py PyArray1<f64> {
+        Forecaster::predict(self, h, Some(&unwrap_x(x, h))).into_pyarray(py)
+    }
+
+    #[pyo3(name = "predict_interval", signature = (h, x = None, level = 0.95, n_paths = 1000))]
+    fn py_predict_interval<'py>(&self, py: Python<'py>, h: usize, x: Option<PyArrayLike2<'py, f64>>, level: f64, n_paths: usize
+) -> (&'py PyArray1<f64>, &'py PyArray1<f64>) {
+        let (lower, upper) = self.predict_interval(h, Some(&unwrap_x(x, h)), level, n_paths);
+        (lower.into_pyarray(py), upper.into_pyarray(py))
+    }
+
+    #[pyo3(name = "forecast", signature = (y, h, x = None, x_future = None))]
+    fn py_forecast<'py>(&mut self, py: Python<'py>, y: PyArrayLike1<'py, f64>, h: usize, x: Option<PyArrayLike2<'py, f64>>, x_future: Option<PyArrayLike2<'py, f64>>
+) -> &'py PyArray1<f64> {
+        Forecaster::forecast(self, &y.as_array().to_owned(), h, Some(&unwrap_x(x, y.len())), Some(&unwrap_x(x_future, h))).into_pyarray(py)
+    }
+
+    #[getter]
+    #[pyo3(name = "weights")]
+    fn py_weights<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+        self.weights().to_owned().into_pyarray(py)
+    }
+}
+
+
+/// Fourier terms for t = start, ..., start + n - 1, see [Fourier::terms]
+#[pyfunction]
+#[pyo3(name = "fourier", signature = (periods, k, start, n))]
+fn py_fourier<'py>(py: Python<'py>, periods: Vec<f64>, k: Vec<usize>, start: usize, n: usize) -> &'py PyArray2<f64> {
+    Fourier::new(periods, k).terms(start, n).into_pyarray(py)
+}
+
+/// k for each period minimising the AICc of model fit with the Fourier terms, see [Fourier::select_k]
+#[pyfunction]
+#[pyo3(name = "select_fourier_k", signature = (model, y, periods, max_k, x = None))]
+fn py_select_fourier_k<'py>(
+    model: PyRef<Model>,
+    y: PyArrayLike1<'py, f64>,
+    periods: Vec<f64>,
+    max_k: Vec<usize>,
+    x: Option<PyArrayLike2<'py, f64>>
+) -> Vec<usize> {
+    let x = x.map(|x| x.as_array().to_owned());
+    Fourier::select_k(&model, &y.as_array().to_owned(), x.as_ref(), periods, max_k).k().to_vec()
+}
+
+
+/// rolling-origin forecasts of a Model or benchmark forecaster on y, see [Backtest::run]
+#[pyfunction]
+#[pyo3(name = "backtest", signature = (model, y, initial, horizon, x = None, step = 1, refit_every = 1, window = "expanding", n_jobs = 0))]
+#[allow(clippy::too_many_arguments)]
+fn py_backtest<'py>(
+    py: Python<'py>,
+    model: &PyAny,
+    y: PyArrayLike1<'py, f64>,
+    initial: usize,
+    horizon: usize,
+    x: Option<PyArrayLike2<'py, f64>>,
+    step: usize,
+    refit_every: usize,
+    window: &str,
+    n_jobs: usize
+) -> PyResult<&'py PyDict> {
+    if initial == 0 || horizon == 0 || step == 0 || refit_every == 0 {
+        return Err(PyValueError::new_err("initial, horizon, step and refit_every must be at least 1."));
+    }
+    let backtest = Backtest::new(initial, horizon)
+        .step(step)
+        .refit_every(refit_every)
+        .window(window.parse().map_err(PyValueError::new_err)?)
+        .n_jobs(n_jobs);
+    let (y, x) = (y.as_array().to_owned(), unwrap_x(x, y.len()));
+    let model = forecaster(model)?;
+    let result = py.allow_threads(|| backtest.run(model.as_ref(), &y, Some(&x)));
+
+    let dict = PyDict::new(py);
+    dict.set_item("origins", result.origins.clone().into_pyarray(py))?;
+    dict.set_item("errors", result.errors().into_pyarray(py))?;
+    dict.set_item("horizon_mae", result.horizon_mae().into_pyarray(py))?;
+    dict.set_item("horizon_rmse", result.horizon_rmse().into_pyarray(py))?;
+    dict.set_item("mae", result.mae())?;
+    dict.set_item("rmse", result.rmse())?;
+    dict.set_item("mape", result.metric(metrics::mape))?;
+    dict.set_item("smape", result.metric(metrics::smape))?;
+    dict.set_item("bias", result.metric(metrics::bias))?;
+    dict.set_item("forecasts", result.forecasts.into_pyarray(py))?;
+    dict.set_item("actuals", result.actuals.into_pyarray(py))?;
+    Ok(dict)
+}
+
+
+/// forecast accuracy metrics, see [metrics]
+#[pyfunction]
+#[pyo3(name = "mae")]
+fn py_mae(actual: PyArrayLike1<f64>, forecast: PyArrayLike1<f64>) -> f64 {
+    metrics::mae(&actual.as_array().to_owned(), &forecast.as_array().to_owned())
+}
+
+#[pyfunction]
+#[pyo3(name = "rmse")]
+fn py_rmse(actual: PyArrayLike1<f64>, forecast: PyArrayLike1<f64>) -> f64 {
+    metrics::rmse(&actual.as_array().to_owned(), &forecast.as_array().to_owned())
+}
+
+#[pyfunction]
+#[pyo3(name = "mape")]
+fn py_mape(actual: PyArrayLike1<f64>, forecast: PyArrayLike1<f64>) -> f64 {
+    metrics::mape(&actual.as_array().to_owned(), &forecast.as_array().to_owned())
+}
+
+#[pyfunction]
+#[pyo3(name = "smape")]
+fn py_smape(actual: PyArrayLike1<f64>, forecast: PyArrayLike1<f64>) -> f64 {
+    metrics::smape(&actual.as_array().to_owned(), &forecast.as_array().to_owned())
+}
+
+#[pyfunction]
+#[pyo3(name = "bias")]
+fn py_bias(actual: PyArrayLike1<f64>, forecast: PyArrayLike1<f64>) -> f64 {
+    metrics::bias(&actual.as_array().to_owned(), &forecast.as_array().to_owned())
+}
+
+#[pyfunction]
+#[pyo3(name = "mase", signature = (actual, forecast, train, s = 1))]
+fn py_mase(actual: PyArrayLike1<f64>, forecast: PyArrayLike1<f64>, train: PyArrayLike1<f64>, s: usize) -> f64 {
+    metrics::mase(&actual.as_array().to_owned(), &forecast.as_array().to_owned(), &train.as_array().to_owned(), s)
+}
+
+#[pyfunction]
+#[pyo3(name = "rmsse", signature = (actual, forecast, train, s = 1))]
+fn py_rmsse(actual: PyArrayLike1<f64>, forecast: PyArrayLike1<f64>, train: PyArrayLike1<f64>, s: usize) -> f64 {
+    metrics::rmsse(&actual.as_array().to_owned(), &forecast.as_array().to_owned(), &train.as_array().to_owned(), s)
+}
+
+#[pyfunction]
+#[pyo3(name = "pinball")]
+fn py_pinball(actual: PyArrayLike1<f64>, quantile: PyArrayLike1<f64>, tau: f64) -> f64 {
+    metrics::pinball(&actual.as_array().to_owned(), &quantile.as_array().to_owned(), tau)
+}
+
+#[pyfunction]
+#[pyo3(name = "coverage")]
+fn py_coverage(actual: PyArrayLike1<f64>, lower: PyArrayLike1<f64>, upper: PyArrayLike1<f64>) -> f64 {
+    metrics::coverage(&actual.as_array().to_owned(), &lower.as_array().to_owned(), &upper.as_array().to_owned())
+}
+
+#[pyfunction]
+#[pyo3(name = "winkler", signature = (actual, lower, upper, level = 0.95))]
+fn py_winkler(actual: PyArrayLike1<f64>, lower: PyArrayLike1<f64>, upper: PyArrayLike1<f64>, level: f64) -> f64 {
+    metrics::winkler(&actual.as_array().to_owned(), &lower.as_array().to_owned(), &upper.as_array().to_owned(), level)
+}
+
+#[pyfunction]
+#[pyo3(name = "crps")]
+fn py_crps(actual: PyArrayLike1<f64>, paths: PyArrayLike2<f64>) -> f64 {
+    metrics::crps(&actual.as_array().to_owned(), &paths.as_array().to_owned())
+}
+
+
 #[pymodule]
-#[pyo3(name = "arima")]
+#[pyo3(name = "_arima")]
 fn arima<'py>(_py: Python<'py>, m: &'py PyModule) -> PyResult<()> {
     // https://pyo3.rs/v0.20.3/class    
     m.add_class::<Model>()?;
+    m.add_class::<Naive>()?;
+    m.add_class::<SeasonalNaive>()?;
+    m.add_class::<Drift>()?;
+    m.add_class::<Mean>()?;
+    m.add_class::<Ensemble>()?;
+    m.add_function(wrap_pyfunction!(py_fourier, m)?)?;
+    m.add_function(wrap_pyfunction!(py_select_fourier_k, m)?)?;
+    m.add_function(wrap_pyfunction!(py_backtest, m)?)?;
+    for metric in [
+        wrap_pyfunction!(py_mae, m)?, wrap_pyfunction!(py_rmse, m)?, wrap_pyfunction!(py_mape, m)?,
+        wrap_pyfunction!(py_smape, m)?, wrap_pyfunction!(py_bias, m)?, wrap_pyfunction!(py_mase, m)?,
+        wrap_pyfunction!(py_rmsse, m)?, wrap_pyfunction!(py_pinball, m)?, wrap_pyfunction!(py_coverage, m)?,
+        wrap_pyfunction!(py_winkler, m)?, wrap_pyfunction!(py_crps, m)?
+    ] {
+        m.add_function(metric)?;
+    }
     Ok(())
 }
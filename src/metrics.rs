@@ -0,0 +1,171 @@
+//! Forecast accuracy metrics comparing actual values with forecasts of the same length.
+//! Pairs where either is NaN are skipped, e.g. horizons past the end of a backtest.
+//!
+//! Errors are actual - forecast, and percentage metrics are in percent as in
+//! [Forecasting: Principles and Practice](https://otexts.com/fpp3/accuracy.html).
+use numpy::ndarray::{Array1, Array2, Axis};
+
+
+/// mean absolute error
+pub fn mae(actual: &Array1<f64>, forecast: &Array1<f64>) -> f64 {
+    mean(pairs(actual, forecast).map(|(a, f)| (a - f).abs()))
+}
+
+/// root mean squared error
+pub fn rmse(actual: &Array1<f64>, forecast: &Array1<f64>) -> f64 {
+    mean(pairs(actual, forecast).map(|(a, f)| (a - f).powi(2))).sqrt()
+}
+
+/// mean absolute percentage error
+pub fn mape(actual: &Array1<f64>, forecast: &Array1<f64>) -> f64 {
+    100. * mean(pairs(actual, forecast).map(|(a, f)| ((a - f) / a).abs()))
+}
+
+/// symmetric mean absolute percentage error, 200 |a - f| / (|a| + |f|) on average
+pub fn smape(actual: &Array1<f64>, forecast: &Array1<f64>) -> f64 {
+    100. * mean(pairs(actual, forecast).map(|(a, f)| 2. * (a - f).abs() / (a.abs() + f.abs())))
+}
+
+/// mean error, the mean of actual - forecast, negative when forecasts are too high
+pub fn bias(actual: &Array1<f64>, forecast: &Array1<f64>) -> f64 {
+    mean(pairs(actual, forecast).map(|(a, f)| a - f))
+}
+
+/// mean absolute scaled error, MAE relative to the in-sample MAE of the seasonal naive forecast y_t = y_t-s
+/// - train: timeseries the forecasts were fit on
+/// - s: seasonal period, 1 for the naive forecast
+pub fn mase(actual: &Array1<f64>, forecast: &Array1<f64>, train: &Array1<f64>, s: usize) -> f64 {
+    mae(actual, forecast) / mean(naive_errors(train, s).map(f64::abs))
+}
+
+/// root mean squared scaled error, RMSE relative to the in-sample RMSE of the seasonal naive forecast y_t = y_t-s
+/// - train: timeseries the forecasts were fit on
+/// - s: seasonal period, 1 for the naive forecast
+pub fn rmsse(actual: &Array1<f64>, forecast: &Array1<f64>, train: &Array1<f64>, s: usize) -> f64 {
+    rmse(actual, forecast) / mean(naive_errors(train, s).map(|e| e * e)).sqrt()
+}
+
+/// pinball (quantile) loss of forecasts of the tau quantile, e.g. 0.9
+pub fn pinball(actual: &Array1<f64>, quantile: &Array1<f64>, tau: f64) -> f64 {
+    if tau <= 0. || tau >= 1. {
+        panic!("tau should be between 0 and 1, got {}.", tau);
+    }
+    mean(pairs(actual, quantile).map(|(a, q)| (tau * (a - q)).max((tau - 1.) * (a - q))))
+}
+
+/// share of actual values within the prediction intervals [lower, upper]
+pub fn coverage(actual: &Array1<f64>, lower: &Array1<f64>, upper: &Array1<f64>) -> f64 {
+    mean(triples(actual, lower, upper).map(|(a, l, u)| (l <= a && a <= u) as u8 as f64))
+}
+
+/// Winkler score of prediction intervals with coverage level, e.g. 0.95: the width of the interval
+/// plus 2 / (1 - level) times the distance of actual values outside it
+pub fn winkler(actual: &Array1<f64>, lower: &Array1<f64>, upper: &Array1<f64>, level: f64) -> f64 {
+    if level <= 0. || level >= 1. {
+        panic!("level should be between 0 and 1, got {}.", level);
+    }
+    let penalty = 2. / (1. - level);
+    mean(triples(actual, lower, upper).map(|(a, l, u)| {
+        (u - l) + penalty * ((l - a).max(0.) + (a - u).max(0.))
+    }))
+}
+
+/// continuous ranked probability score of forecast distributions given by simulated paths,
+/// E|X - a| - E|X - X'| / 2 for each horizon averaged over horizons
+/// - paths: one row per simulated path and one column per horizon, same number of columns as the length of actual
+pub fn crps(actual: &Array1<f64>, paths: &Array2<f64>) -> f64 {
+    if paths.shape()[1] != actual.len() {
+        panic!("paths has {} columns. It should have {}.", paths.shape()[1], actual.len());
+    }
+    mean(actual.iter().zip(paths.axis_iter(Axis(1))).filter(|(a, _)| !a.is_nan()).map(|(&a, samples)| {
+        let mut samples: Vec<f64> = samples.iter().copied().filter(|v| !v.is_nan()).collect();
+        samples.sort_by(f64::total_cmp);
+        let m = samples.len() as f64;
+        let accuracy = samples.iter().map(|x| (x - a).abs()).sum::<f64>() / m;
+        // E|X - X'| from the order statistics
+        let spread = samples.iter().enumerate().map(|(i, x)| (2. * i as f64 + 1. - m) * x).sum::<f64>() * 2. / (m * m);
+        accuracy - spread / 2.
+    }))
+}
+
+/// mean of the values that are not NaN
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.filter(|v| !v.is_nan()).fold((0., 0), |(sum, count), v| (sum + v, count + 1));
+    sum / count as f64
+}
+
+fn pairs<'a>(actual: &'a Array1<f64>, forecast: &'a Array1<f64>) -> impl Iterator<Item = (f64, f64)> + 'a {
+    if actual.len() != forecast.len() {
+        panic!("actual is length: {}. It should be length: {}.", actual.len(), forecast.len());
+    }
+    actual.iter().zip(forecast.iter()).map(|(&a, &f)| (a, f)).filter(|(a, f)| !a.is_nan() && !f.is_nan())
+}
+
+fn triples<'a>(actual: &'a Array1<f64>, lower: &'a Array1<f64>, upper: &'a Array1<f64>) -> impl Iterator<Item = (f64, f64, f64)> + 'a {
+    if upper.len() != lower.len() {
+        panic!("upper is length: {}. It should be length: {}.", upper.len(), lower.len());
+    }
+    pairs(actual, lower)
+        .zip(upper.iter())
+        .map(|((a, l), &u)| (a, l, u))
+        .filter(|(_, _, u)| !u.is_nan())
+}
+
+/// in-sample errors of the seasonal naive forecast, y_t - y_t-s
+fn naive_errors(train: &Array1<f64>, s: usize) -> impl Iterator<Item = f64> + '_ {
+    if s == 0 || s >= train.len() {
+        panic!("train is length: {}. It should be longer than s: {}.", train.len(), s);
+    }
+    (s..train.len()).map(move |t| train[t] - train[t - s])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::{arr1, arr2};
+
+    fn round(v: f64) -> f64 {
+        (1e9 * v).round() / 1e9
+    }
+
+    #[test]
+    fn metrics_point() {
+        let actual = arr1(&[2., 4., f64::NAN, 5.]);
+        let forecast = arr1(&[1., 5., 3., 8.]);
+        assert_eq!(mae(&actual, &forecast), 5. / 3.);
+        assert_eq!(rmse(&actual, &forecast), (11. / 3_f64).sqrt());
+        assert_eq!(round(mape(&actual, &forecast)), round(100. * (0.5 + 0.25 + 0.6) / 3.));
+        assert_eq!(round(smape(&actual, &forecast)), round(100. * (2. / 3. + 2. / 9. + 6. / 13.) / 3.));
+        assert_eq!(bias(&actual, &forecast), -1.);
+    }
+
+    #[test]
+    fn metrics_scaled() {
+        let train = arr1(&[1., 3., 2., 4., 3., 5.]);
+        let (actual, forecast) = (arr1(&[6., 4.]), arr1(&[5., 6.]));
+        // naive errors 2, -1, 2, -1, 2 and seasonal (s = 2) errors 1, 1, 1, 1
+        assert_eq!(mase(&actual, &forecast, &train, 1), 1.5 / 1.6);
+        assert_eq!(mase(&actual, &forecast, &train, 2), 1.5);
+        assert_eq!(rmsse(&actual, &forecast, &train, 1), 2.5_f64.sqrt() / 2.8_f64.sqrt());
+    }
+
+    #[test]
+    fn metrics_intervals() {
+        let actual = arr1(&[1., 5., 10.]);
+        let (lower, upper) = (arr1(&[0., 0., 0.]), arr1(&[2., 4., 12.]));
+        assert_eq!(coverage(&actual, &lower, &upper), 2. / 3.);
+        // widths 2, 4, 12 and a penalty of 2 / 0.2 for the actual 5 one above its upper bound
+        assert_eq!(round(winkler(&actual, &lower, &upper, 0.8)), round((2. + 4. + 10. + 12.) / 3.));
+        assert_eq!(pinball(&arr1(&[3.]), &arr1(&[1.]), 0.9), 1.8);
+        assert_eq!(round(pinball(&arr1(&[1.]), &arr1(&[3.]), 0.9)), 0.2);
+    }
+
+    #[test]
+    fn metrics_crps() {
+        // a point forecast reduces to the absolute error
+        assert_eq!(crps(&arr1(&[1., 2.]), &arr2(&[[3., 2.]])), 1.);
+        // samples 0 and 2 for actual 1: E|X - a| = 1, E|X - X'| = 1
+        assert_eq!(crps(&arr1(&[1.]), &arr2(&[[0.], [2.]])), 0.5);
+    }
+}
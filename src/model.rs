@@ -1,30 +1,78 @@
 mod prepare_data;
 mod fit_predict;
+mod builder;
+mod polynomial;
+mod transform;
+mod interval;
+mod regression;
+mod optimise;
+mod stationarity;
+mod simulate;
+#[cfg(test)]
+mod recovery;
+pub use builder::ModelBuilder;
+pub use transform::Transform;
+pub use simulate::Noise;
+pub(crate) use interval::normal_quantile;
 
-use numpy::ndarray::{Array1, Array2};
+use std::fmt;
+use std::str::FromStr;
+use numpy::ndarray::{Array1, Array2, Axis, concatenate, s};
 use pyo3::pyclass;
 
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[pyclass(name = "Model", module = "arima")]
 pub struct Model {
     // order: (AR(p), I(d), MA(q), 1)
-    // seasonal_order: (AR(p), I(d), MA(q), s)
+    // seasonal_orders: (AR(p), I(d), MA(q), s) for each seasonal period
     // exog_fit: exongenous variables used for fitting
     // endog_fit: time-series
     // coefs_fit: last coefficients from fitting
+    // trend: deterministic terms in the (differenced) equation
+    // seasonality: how seasonal and non-seasonal AR and MA polynomials are combined
+    // method: how coefficients are estimated
+    // future_errors: how future MA innovations are forecast
+    // exog: which exogenous variables are differenced along with y
+    // regression: how exogenous variables enter the model
+    // exog_lags: lags of each exogenous variable, empty for contemporaneous values only
+    // missing: how missing values (NaN) in y and x are handled
+    // transform: transformation of y before differencing
+    // bias_adjust: back-transform predictions to the mean rather than the median
+    // enforce_stationarity: keep estimated AR polynomials stationary
+    // enforce_invertibility: keep estimated MA polynomials invertible
+    // lambda: Box-Cox λ resolved when fitting
+    // sigma2: variance of the one-step-ahead errors on the transformed, differenced scale
+    // errors_fit: one-step-ahead errors on the transformed scale aligned with endog_fit, NaN without a prediction
+    // nobs: number of observations used for estimation
     order: Order,
-    seasonal_order: Order,
+    seasonal_orders: Vec<Order>,
+    seasonality: Seasonality,
+    trend: Trend,
+    method: Method,
+    future_errors: FutureErrors,
+    exog: Exog,
+    regression: Regression,
+    exog_lags: Vec<Vec<usize>>,
+    missing: Missing,
+    transform: Transform,
+    bias_adjust: bool,
+    enforce_stationarity: bool,
+    enforce_invertibility: bool,
+    lambda: Option<f64>,
     endog_fit: Option<Array1<f64>>,
     exog_fit: Option<Array2<f64>>,
-    pub coefs: Option<Array1<f64>>
+    pub coefs: Option<Array1<f64>>,
+    sigma2: Option<f64>,
+    errors_fit: Option<Array1<f64>>,
+    nobs: Option<usize>
 }
 
 /// p: AR (auto regressive) terms
 /// d: I (integrated) terms
 /// q: MA (moving average) terms
 /// s: periodicity
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Clone, Debug)]
 struct Order {
     p: usize,
     d: usize,
@@ -32,32 +80,489 @@ struct Order {
     s: usize
 }
 
+/// Deterministic terms included in the (differenced) model
+/// - N: no trend
+/// - C: constant, which becomes a drift in levels when d + D = 1
+/// - T: linear time trend
+/// - Ct: constant and linear time trend
+/// 
+/// Like R's `Arima`, trends that integrate to a polynomial of degree 2 or more in levels
+/// (e.g. a constant with d + D = 2) are rejected unless explicitly allowed with
+/// [ModelBuilder::allow_high_order_trend].
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Trend {
+    N,
+    C,
+    T,
+    Ct
+}
+
+impl Trend {
+    fn has_constant(&self) -> bool {
+        matches!(self, Trend::C | Trend::Ct)
+    }
+
+    fn has_time(&self) -> bool {
+        matches!(self, Trend::T | Trend::Ct)
+    }
+
+    /// number of deterministic columns in the design matrix
+    fn n_cols(&self) -> usize {
+        self.has_constant() as usize + self.has_time() as usize
+    }
+
+    /// degree of the polynomial in time, if any, that the trend adds to the differenced series
+    fn degree(&self) -> Option<usize> {
+        match self {
+            Trend::N => None,
+            Trend::C => Some(0),
+            Trend::T | Trend::Ct => Some(1)
+        }
+    }
+}
+
+impl FromStr for Trend {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "n" => Ok(Trend::N),
+            "c" => Ok(Trend::C),
+            "t" => Ok(Trend::T),
+            "ct" => Ok(Trend::Ct),
+            _ => Err(format!("Unknown trend: {}. It should be one of: n, c, t, ct.", s))
+        }
+    }
+}
+
+/// How the AR and MA polynomials of the non-seasonal and seasonal terms are combined
+/// - Multiplicative: (1 - φ_1 B - ...)(1 - Φ_1 B^s - ...) as in R and statsmodels, which implies cross terms
+///   such as φ_1 Φ_1 at lag s + 1. Coefficients are estimated by conditional sum of squares starting from
+///   the additive estimates.
+/// - Additive: 1 - φ_1 B - ... - Φ_1 B^s - ..., a regression on the lags without cross terms
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Seasonality {
+    Multiplicative,
+    Additive
+}
+
+impl FromStr for Seasonality {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "multiplicative" => Ok(Seasonality::Multiplicative),
+            "additive" => Ok(Seasonality::Additive),
+            _ => Err(format!("Unknown seasonality: {}. It should be one of: multiplicative, additive.", s))
+        }
+    }
+}
+
+/// Estimation method
+/// - Recursive: coefficients are re-estimated by least squares at every step so that the
+///   one-step-ahead errors used as MA regressors only depend on past observations
+/// - HannanRissanen: errors are first estimated by a long autoregression, then all
+///   coefficients are estimated with a single least squares regression
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Method {
+    Recursive,
+    HannanRissanen
+}
+
+impl FromStr for Method {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recursive" => Ok(Method::Recursive),
+            "hannan-rissanen" => Ok(Method::HannanRissanen),
+            _ => Err(format!("Unknown method: {}. It should be one of: recursive, hannan-rissanen.", s))
+        }
+    }
+}
+
+/// Future MA innovations used for forecasting
+/// - Zero: their expectation under the model, with past innovations the one-step-ahead errors of the final coefficients
+/// - Autoregressive: forecasts of an AR model, with the same AR orders, of the one-step-ahead errors of the
+///   final coefficients and the exogenous variables. A heuristic, not the expectation under the model.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum FutureErrors {
+    Zero,
+    Autoregressive
+}
+
+impl FromStr for FutureErrors {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zero" => Ok(FutureErrors::Zero),
+            "autoregressive" => Ok(FutureErrors::Autoregressive),
+            _ => Err(format!("Unknown future errors: {}. It should be one of: zero, autoregressive.", s))
+        }
+    }
+}
+
+/// Handling of exogenous variables
+/// - Differenced: differenced with the same d, D and s as y
+/// - Levels: enter the differenced equation as they are, e.g. holiday dummies or interventions
+/// - Columns: per column choice, true to difference and false to keep in levels
+#[derive(PartialEq, Clone, Debug)]
+pub enum Exog {
+    Differenced,
+    Levels,
+    Columns(Vec<bool>)
+}
+
+impl Exog {
+    /// whether column j is differenced
+    fn is_differenced(&self, j: usize) -> bool {
+        match self {
+            Exog::Differenced => true,
+            Exog::Levels => false,
+            Exog::Columns(columns) => columns[j]
+        }
+    }
+}
+
+impl FromStr for Exog {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "differenced" => Ok(Exog::Differenced),
+            "levels" => Ok(Exog::Levels),
+            _ => Err(format!("Unknown exog: {}. It should be one of: differenced, levels.", s))
+        }
+    }
+}
+
+/// How exogenous variables enter the model
+/// - Armax: next to the lags of the differenced y, so coefficients are effects on the differenced y
+///   given its past, which accumulate through the AR terms
+/// - SarimaErrors: [regression with SARIMA errors](https://robjhyndman.com/hyndsight/arimax/),
+///   y_t = x_t β + η_t where η_t follows the SARIMA model with trend, so β are marginal effects in levels.
+///   β and the SARIMA coefficients are estimated jointly by feasible generalised least squares
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Regression {
+    Armax,
+    SarimaErrors
+}
+
+impl FromStr for Regression {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "armax" => Ok(Regression::Armax),
+            "sarima-errors" => Ok(Regression::SarimaErrors),
+            _ => Err(format!("Unknown regression: {}. It should be one of: armax, sarima-errors.", s))
+        }
+    }
+}
+
+/// Handling of missing values (NaN) in y and x used for fitting
+/// - Raise: panic when fitting
+/// - Skip: rows with missing values or missing lags are skipped in estimation and missing values of y
+///   are replaced by their one-step-ahead prediction so that forecasts can continue after gaps.
+///   The first d + D * s observations of y must not be missing, nor x on those rows with SARIMA errors,
+///   nor the values of x that predict a missing y.
+/// 
+/// Future exogenous variables passed to predict must never be missing.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Missing {
+    Raise,
+    Skip
+}
+
+impl FromStr for Missing {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raise" => Ok(Missing::Raise),
+            "skip" => Ok(Missing::Skip),
+            _ => Err(format!("Unknown missing: {}. It should be one of: raise, skip.", s))
+        }
+    }
+}
+
 /// # Train and forecast
 /// 
 impl Model {
     /// - y: timeseries
     /// - x: exogenous variables, same length as y
     pub fn fit(&mut self, y: &Array1<f64>, x: Option<&Array2<f64>>) {
+        let x = self.check_data(y, x);
+        self.fit_transform(y);
         self.endog_fit = Some(y.to_owned());
-        self.exog_fit = Some(self.unwrap_x(x, y.len()));
+        self.exog_fit = Some(x);
+        self.estimate();
+    }
+
+    /// adds observations after those used for fitting, so that predictions follow them
+    /// - y: new observations of the timeseries
+    /// - x: exogenous variables, same length as y
+    /// - refit: whether to estimate the coefficients and transformation again on all observations.
+    ///   Otherwise they are kept, as are σ² and the number of observations, and only the one-step-ahead
+    ///   errors of the new observations are added, filtering on from the last earlier ones. Without missing
+    ///   values neither this nor the forecasts need more than the last observations.
+    pub fn append(&mut self, y: &Array1<f64>, x: Option<&Array2<f64>>, refit: bool) {
+        if self.coefs.is_none() {
+            panic!("Model must be fit before append");
+        }
+        let x = self.check_data(y, x);
+        let endog_fit = concatenate![Axis(0), self.endog_fit.as_ref().unwrap().view(), y.view()];
+        let exog_fit = concatenate![Axis(0), self.exog_fit.as_ref().unwrap().view(), x.view()];
+        if refit {
+            self.fit_transform(&endog_fit);
+        }
+        self.endog_fit = Some(endog_fit);
+        self.exog_fit = Some(exog_fit);
+        if refit {
+            self.estimate();
+        } else {
+            self.errors_fit = Some(self.filter_appended(y.len()));
+        }
+    }
+
+    /// returns a model for another series with the coefficients and transformation estimated by fit, e.g. to forecast
+    /// short series with the dynamics of a long one. Its one-step-ahead errors, differencing and integration
+    /// use the new data, and σ² and the number of observations are those of this model.
+    /// With a time trend its time restarts at 1 on the first observation of the new series.
+    /// - y: timeseries
+    /// - x: exogenous variables, same length as y, with the columns used for fitting
+    pub fn apply(&self, y: &Array1<f64>, x: Option<&Array2<f64>>) -> Model {
+        if self.coefs.is_none() {
+            panic!("Model must be fit before apply");
+        }
+        let x = self.check_data(y, x);
+        let mut model = self.clone();
+        model.endog_fit = Some(y.to_owned());
+        model.exog_fit = Some(x);
+        model.errors_fit = Some(model.filter());
+        model
+    }
+
+    /// x, or no exogenous variables, checked against the model specification and missing values
+    fn check_data(&self, y: &Array1<f64>, x: Option<&Array2<f64>>) -> Array2<f64> {
+        let x = self.unwrap_x(x, y.len());
+        if let Exog::Columns(columns) = &self.exog {
+            if columns.len() != x.shape()[1] {
+                panic!("Differencing is set for {} exog columns but x has {}.", columns.len(), x.shape()[1]);
+            }
+        }
+        if !self.exog_lags.is_empty() && self.exog_lags.len() != x.shape()[1] {
+            panic!("Lags are set for {} exog columns but x has {}.", self.exog_lags.len(), x.shape()[1]);
+        }
+        if self.missing == Missing::Raise {
+            if y.iter().any(|v| v.is_nan()) {
+                panic!("y contains missing values (NaN). Use Missing::Skip to estimate around them.")
+            }
+            if x.iter().any(|v| v.is_nan()) {
+                panic!("x contains missing values (NaN). Use Missing::Skip to estimate around them.")
+            }
+        }
+        if self.missing == Missing::Skip {
+            self.check_missing(y, &x);
+        }
+        x
+    }
+
+    /// missing values of y are filled from their one-step-ahead predictions and the levels before them,
+    /// so the first d + D * s observations (and x with SARIMA errors) must be present and so must
+    /// the values of x that predict a missing y
+    fn check_missing(&self, y: &Array1<f64>, x: &Array2<f64>) {
+        let k = self.nobs_differenced().min(y.len());
+        let with_errors = self.regression == Regression::SarimaErrors;
+        if let Some(t) = (0..k).find(|&t| y[t].is_nan() || (with_errors && x.row(t).iter().any(|v| v.is_nan()))) {
+            panic!(
+                "Observation {} is missing (NaN). The first {} observations are lost to differencing and must not be missing.",
+                t, k
+            );
+        }
+        if with_errors {
+            return;
+        }
+        for t in (0..y.len()).filter(|&t| y[t].is_nan()) {
+            for j in 0..x.shape()[1] {
+                let span = if self.exog.is_differenced(j) {k} else {0};
+                for lag in self.exog_lags(j).into_iter().filter(|&lag| lag <= t) {
+                    if ((t - lag).saturating_sub(span)..=t - lag).any(|i| x[[i, j]].is_nan()) {
+                        panic!("y is missing (NaN) at {} and so is x column {} that predicts it, so it can't be filled.", t, j);
+                    }
+                }
+            }
+        }
+    }
+
+    /// estimates the coefficients, σ² and one-step-ahead errors from the data used for fitting
+    fn estimate(&mut self) {
+        let exog_fit = self.exog_fit.as_ref().unwrap();
+        let endog_fit = self.transform_y(self.endog_fit.as_ref().unwrap());
+
+        let (coefs, errors, sigma2, nobs) = if self.regression == Regression::SarimaErrors && self.n_exog() > 0 {
+            self.fit_regression_errors(exog_fit, &endog_fit)
+        } else {
+            let exog_future: Array2<f64> = Array2::zeros((0, exog_fit.shape()[1]));
+            let (exog_diff, endog_diff) = self.difference_xy(exog_fit, &exog_future, &endog_fit, 0);
+            let (mut x, y) = self.prepare_xy(&exog_diff, &endog_diff, 0);
+            self.fit_coefs(&y, &mut x)
+        };
+
+        self.errors_fit = Some(align_errors(endog_fit.len(), errors));
+        self.coefs = Some(coefs);
+        self.sigma2 = Some(sigma2);
+        self.nobs = Some(nobs);
+    }
+
+    /// one-step-ahead errors of the data used for fitting with the estimated coefficients
+    fn filter(&self) -> Array1<f64> {
+        let exog_fit = self.exog_fit.as_ref().unwrap();
+        let endog_fit = self.transform_y(self.endog_fit.as_ref().unwrap());
+        let coefs = self.coefs.as_ref().unwrap();
+
+        if self.regression == Regression::SarimaErrors && self.n_exog() > 0 {
+            self.filter_regression_errors(exog_fit, &endog_fit, coefs)
+        } else {
+            let exog_future: Array2<f64> = Array2::zeros((0, exog_fit.shape()[1]));
+            let (exog_diff, endog_diff) = self.difference_xy(exog_fit, &exog_future, &endog_fit, 0);
+            let (mut x, y) = self.prepare_xy(&exog_diff, &endog_diff, 0);
+            align_errors(endog_fit.len(), self.one_step_errors(&y, &mut x, coefs))
+        }
+    }
+
+    /// one-step-ahead errors after n_new observations were appended, continuing the filter from the errors of the
+    /// earlier ones over the new ones when the last observations before them are not missing
+    fn filter_appended(&self, n_new: usize) -> Array1<f64> {
+        let errors_fit = self.errors_fit.as_ref().unwrap();
+        let exog_fit = self.exog_fit.as_ref().unwrap();
+        let endog_fit = self.transform_y(self.endog_fit.as_ref().unwrap());
+        let coefs = self.coefs.as_ref().unwrap();
+
+        let n_old = endog_fit.len() - n_new;
+        let start = n_old.saturating_sub(self.state_len(coefs));
+        let (endog_state, exog_state) = (endog_fit.slice(s![start..n_old]), exog_fit.slice(s![start..n_old, ..]));
+        if start == 0
+            || (self.regression == Regression::SarimaErrors && self.n_exog() > 0)
+            || endog_state.iter().chain(exog_state.iter()).any(|v| v.is_nan()) {
+            return self.filter();
+        }
+
+        let (endog, exog) = (endog_fit.slice(s![start..]).to_owned(), exog_fit.slice(s![start.., ..]).to_owned());
+        let exog_future: Array2<f64> = Array2::zeros((0, exog.shape()[1]));
+        let (exog_diff, endog_diff) = self.difference_xy(&exog, &exog_future, &endog, 0);
+        let (mut x, mut y) = self.prepare_xy(&exog_diff, &endog_diff, start);
+        let n_known = y.len() - n_new;
+        let known = errors_fit.slice(s![n_old - n_known..]).mapv(|e| if e.is_nan() {0.} else {e});
+        let errors_new = self.continue_errors(&known, &mut y, &mut x, coefs);
+        concatenate![Axis(0), errors_fit.view(), errors_new.view()]
     }
 
     /// - h: horizons to forecast
-    /// - x: future exongenous variables, same length as h
+    /// - x: future exongenous variables, same length as h. When every exogenous variable only enters
+    ///   with lags of at least k, only the first h - k rows are needed.
     /// 
     /// returns predictions for h horizons
-    pub fn predict(&mut self, h: usize, x: Option<&Array2<f64>>) -> Array1<f64> {
+    pub fn predict(&self, h: usize, x: Option<&Array2<f64>>) -> Array1<f64> {
+        let y_preds = self.predict_transformed(h, x);
+        let variance = if self.bias_adjust {Some(self.forecast_variance(h))} else {None};
+        self.inverse_transform(&y_preds, variance.as_ref())
+    }
 
-        let exog_fit = self.exog_fit.as_ref().expect("Model must be fit before predict");
-        let exog_future = self.unwrap_x(x, h);
+    /// - h: horizons to forecast
+    /// - x: future exongenous variables, same length as h
+    /// - level: coverage of the interval, e.g. 0.95
+    /// 
+    /// returns the lower and upper bounds of the prediction intervals for h horizons,
+    /// assuming normal errors on the transformed scale
+    pub fn predict_interval(&self, h: usize, x: Option<&Array2<f64>>, level: f64) -> (Array1<f64>, Array1<f64>) {
+        if level <= 0. || level >= 1. {
+            panic!("level should be between 0 and 1, got {}.", level);
+        }
+        let y_preds = self.predict_transformed(h, x);
+        let margin = self.forecast_variance(h).mapv(f64::sqrt) * interval::normal_quantile(0.5 + level / 2.);
+
+        let lower = self.inverse_transform(&(&y_preds - &margin), None);
+        let upper = self.inverse_transform(&(&y_preds + &margin), None);
+        (lower, upper)
+    }
+
+    /// - start: index of y used for fitting of the first prediction
+    /// - end: index of the last prediction, inclusive, at or beyond the length of y to forecast past its end
+    /// - dynamic: offset from start from which predictions rather than observations of y feed the lags,
+    ///   from the end of y if None
+    /// - x: future exongenous variables for the horizons beyond y, like `predict` with h = end + 1 - len(y)
+    ///
+    /// returns predictions for start..=end on the original scale: one-step-ahead predictions before start + dynamic
+    /// and forecasts from it on, with the conventions of statsmodels' `get_prediction(start, end, dynamic)`
+    pub fn predict_range(&self, start: usize, end: usize, dynamic: Option<usize>, x: Option<&Array2<f64>>) -> Array1<f64> {
         let endog_fit = self.endog_fit.as_ref().expect("Model must be fit before predict");
+        let exog_fit = self.exog_fit.as_ref().unwrap();
+        let n = endog_fit.len();
+        let origin = dynamic.map_or(n, |dynamic| start + dynamic);
+        if start > end {
+            panic!("start ({}) should not be after end ({}).", start, end);
+        }
+        if origin > n {
+            panic!("start + dynamic ({}) should not be after the end of y ({}).", origin, n);
+        }
 
-        let (exog_diff, endog_diff) = self.difference_xy(exog_fit, &exog_future, endog_fit, h);
-        let (mut x, mut y) = self.prepare_xy(&exog_diff, &endog_diff);
-        
-        let (y_preds, coefs) = self.fit_predict_internal(h, &mut y, &mut x, &exog_diff);
-        self.coefs = Some(coefs);
-        self.integrate_predictions(&y_preds, &endog_fit)
+        let exog_future = self.unwrap_x_future(x, (end + 1).saturating_sub(n));
+        let h = (end + 1).saturating_sub(origin);
+        let forecasts = if h > 0 {
+            let exog_future = concatenate![Axis(0), exog_fit.slice(s![origin.., ..]), exog_future.view()];
+            let endog = self.transform_y(&endog_fit.slice(s![..origin]).to_owned());
+            let errors = self.errors_fit.as_ref().unwrap().slice(s![..origin]).to_owned();
+            let y_preds = self.predict_from(&endog, &exog_fit.slice(s![..origin, ..]).to_owned(), &exog_future, h, Some(&errors));
+            let variance = if self.bias_adjust {Some(self.forecast_variance(h))} else {None};
+            self.inverse_transform(&y_preds, variance.as_ref())
+        } else {
+            Array1::zeros(0)
+        };
+
+        let fitted = self.fitted_values();
+        Array1::from_iter((start..=end).map(|t| if t < origin {fitted[t]} else {forecasts[t - origin]}))
+    }
+
+    /// predictions on the transformed scale with the coefficients estimated by fit
+    fn predict_transformed(&self, h: usize, x: Option<&Array2<f64>>) -> Array1<f64> {
+        let exog_fit = self.exog_fit.as_ref().expect("Model must be fit before predict");
+        let exog_future = self.unwrap_x_future(x, h);
+        let endog_fit = self.transform_y(self.endog_fit.as_ref().unwrap());
+        self.predict_from(&endog_fit, exog_fit, &exog_future, h, self.errors_fit.as_ref())
+    }
+
+    /// h predictions on the transformed scale following endog, already transformed, and exog,
+    /// with the coefficients estimated by fit
+    /// - errors: one-step-ahead errors of endog with these coefficients (NaN without a prediction) if known,
+    ///   so that only the last observations are needed when there are no missing values
+    fn predict_from(&self, endog: &Array1<f64>, exog: &Array2<f64>, exog_future: &Array2<f64>, h: usize, errors: Option<&Array1<f64>>) -> Array1<f64> {
+        let coefs = self.coefs.as_ref().unwrap();
+        if self.regression == Regression::SarimaErrors && self.n_exog() > 0 {
+            return self.predict_regression_errors(h, exog, exog_future, endog, coefs, errors);
+        }
+
+        let start = endog.len().saturating_sub(self.state_len(coefs));
+        let (endog_state, exog_state) = (endog.slice(s![start..]), exog.slice(s![start.., ..]));
+        let known = errors.filter(|_| {
+            self.future_errors == FutureErrors::Zero
+                && !endog_state.iter().chain(exog_state.iter()).any(|v| v.is_nan())
+        });
+
+        match known {
+            Some(errors) => {
+                let (endog, exog) = (endog_state.to_owned(), exog_state.to_owned());
+                let (exog_diff, endog_diff) = self.difference_xy(&exog, exog_future, &endog, h);
+                let (mut x, mut y) = self.prepare_xy(&exog_diff, &endog_diff, start);
+
+                let errors = errors.slice(s![errors.len() + h - y.len()..]).mapv(|e| if e.is_nan() {0.} else {e});
+                let errors = concatenate![Axis(0), errors.view(), Array1::zeros(h).view()];
+                let y_preds = self.predict_coefs(h, &mut y, &mut x, coefs, Some(&errors));
+                self.integrate_predictions(&y_preds, &endog)
+            },
+            None => {
+                let (exog_diff, endog_diff) = self.difference_xy(exog, exog_future, endog, h);
+                let (mut x, mut y) = self.prepare_xy(&exog_diff, &endog_diff, 0);
+
+                let y_preds = self.predict_coefs(h, &mut y, &mut x, coefs, None);
+                let endog = self.fill_missing_levels(endog, &endog_diff, &y, h);
+                self.integrate_predictions(&y_preds, &endog)
+            }
+        }
     }
 
     /// - y: timeseries
@@ -67,7 +572,7 @@ impl Model {
     /// 
     /// returns predictions for h horizons
     pub fn forecast(&mut self, y: &Array1<f64>, h: usize, x: Option<&Array2<f64>>, x_future: Option<&Array2<f64>>) -> Array1<f64> {
-        self.fit(&y, x);
+        self.fit(y, x);
         self.predict(h, x_future)
     }
 
@@ -78,7 +583,7 @@ impl Model {
     /// 
     /// returns predictions for h horizons
     pub fn fit_predict(&mut self, y: &Array1<f64>, h: usize, x: Option<&Array2<f64>>, x_future: Option<&Array2<f64>>) -> Array1<f64> {
-        self.forecast(&y, h, x, x_future)
+        self.forecast(y, h, x, x_future)
     }
 
     /// Create a [SARIMA](https://en.wikipedia.org/wiki/Autoregressive_integrated_moving_average#Variations_and_extensions) model.
@@ -91,15 +596,10 @@ impl Model {
     ///     - D: I(D) integrated terms
     ///     - Q: MA(Q) moving average terms
     ///     - s: periodicity
+    /// 
+    /// Use [ModelBuilder] for multiple seasonal periods, trend, estimation method and exogenous variable options.
     pub fn sarima(order: (usize, usize, usize), seasonal_order: (usize, usize, usize, usize)) -> Self {
-        let (p, d, q) = order;
-        let order = Order {p, d, q, s: 1};
-
-        let (p, d, q, s) = seasonal_order;
-        if s == 1 {panic!("It doesn't make sense for periodicity (s) to be set to 1.")}
-        let seasonal_order = Order {p, d, q, s};
-
-        Self {order, seasonal_order, endog_fit: None, exog_fit: None, coefs: None}
+        ModelBuilder::new().order(order).seasonal_order(seasonal_order).build()
     }
 
     /// Create an [ARIMA](https://en.wikipedia.org/wiki/Autoregressive_integrated_moving_average) model
@@ -130,31 +630,135 @@ impl Model {
     }
 }
 
+/// # Inspect
+/// 
+impl Model {
+    /// whether `fit` has been called
+    pub fn is_fitted(&self) -> bool {
+        self.endog_fit.is_some()
+    }
+
+    /// number of exogenous variables used for fitting
+    pub fn n_exog(&self) -> usize {
+        self.exog_fit.as_ref().map_or(0, |x| x.shape()[1])
+    }
+
+    /// one-step-ahead predictions of y used for fitting on the original scale, NaN for the first observations
+    /// lost to differencing and lags and where y is missing
+    pub fn fitted_values(&self) -> Array1<f64> {
+        let errors_fit = self.errors_fit.as_ref().expect("Model must be fit before fitted values");
+        let z = self.transform_y(self.endog_fit.as_ref().unwrap()) - errors_fit;
+        let variance = Array1::from_elem(z.len(), self.sigma2.unwrap());
+        self.inverse_transform(&z, Some(&variance))
+    }
+
+    /// y used for fitting minus the fitted values, on the original scale
+    pub fn residuals(&self) -> Array1<f64> {
+        self.endog_fit.as_ref().expect("Model must be fit before residuals") - &self.fitted_values()
+    }
+
+    /// names of the coefficients in the same order as `coefs`, following statsmodels naming:
+    /// - intercept
+    /// - ma.L{i}, ma.S.L{i * s}: moving average terms
+    /// - ar.L{i}, ar.S.L{i * s}: auto regressive terms
+    /// - x{j}, x{j}.L{l}: exogenous variables and their lags
+    pub fn coef_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if self.trend.has_constant() {names.push("intercept".to_string())}
+        if self.trend.has_time() {names.push("trend".to_string())}
+        names.extend((1..=self.order.q).map(|i| format!("ma.L{}", i)));
+        for so in self.seasonal_orders.iter() {
+            names.extend((1..=so.q).map(|i| format!("ma.S.L{}", i * so.s)));
+        }
+        names.extend((1..=self.order.p).map(|i| format!("ar.L{}", i)));
+        for so in self.seasonal_orders.iter() {
+            names.extend((1..=so.p).map(|i| format!("ar.S.L{}", i * so.s)));
+        }
+        for j in 0..self.n_exog() {
+            names.extend(self.exog_lags(j).iter().map(|&lag| {
+                if lag == 0 {format!("x{}", j)} else {format!("x{}.L{}", j, lag)}
+            }));
+        }
+        names
+    }
+}
+
+/// # Information criteria
+/// 
+/// Gaussian likelihood of the one-step-ahead errors on the transformed, differenced scale,
+/// so only comparable between models of the same y with the same d, D and transformation.
+/// Available once fit has estimated the coefficients.
+impl Model {
+    fn loglik(&self) -> (f64, usize) {
+        let coefs = self.coefs.as_ref().expect("Model must be fit before information criteria");
+        let (sigma2, nobs) = (self.sigma2.unwrap(), self.nobs.unwrap());
+        let n = nobs as f64;
+        let n_params = coefs.len() + 1;
+
+        let dof = nobs.saturating_sub(coefs.len()).max(1) as f64;
+        let sigma2_ml = sigma2 * dof / n;
+        (-n / 2. * ((2. * std::f64::consts::PI * sigma2_ml).ln() + 1.), n_params)
+    }
+
+    /// Akaike information criterion, counting the coefficients and σ² as parameters
+    pub fn aic(&self) -> f64 {
+        let (loglik, k) = self.loglik();
+        -2. * loglik + 2. * k as f64
+    }
+
+    /// AIC corrected for small samples
+    pub fn aicc(&self) -> f64 {
+        let (_, k) = self.loglik();
+        let n = self.nobs.unwrap() as f64;
+        let k = k as f64;
+        if n - k - 1. <= 0. {
+            return f64::INFINITY;
+        }
+        self.aic() + 2. * k * (k + 1.) / (n - k - 1.)
+    }
+}
+
+/// SARIMAX(p,d,q)(P,D,Q,s), with one (P,D,Q,s) for each seasonal period
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let o = &self.order;
+        write!(f, "SARIMAX({},{},{})", o.p, o.d, o.q)?;
+        for so in self.seasonal_orders.iter() {
+            write!(f, "({},{},{},{})", so.p, so.d, so.q, so.s)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// errors of the prepared rows, which are those of the last observations, aligned with the n observations
+fn align_errors(n: usize, errors: Array1<f64>) -> Array1<f64> {
+    let mut aligned = Array1::from_elem(n, f64::NAN);
+    aligned.slice_mut(s![n - errors.len()..]).assign(&errors);
+    aligned
+}
+
 
 #[cfg(test)]
 mod tests {
     // run with "cargo test -- --show-output" to see output
-    use numpy::ndarray::{Array, Array1, arr1, s};
+    use numpy::ndarray::{Array, Array1, Axis, arr1, s};
     use super::*;
+    use crate::random::Rng;
 
     #[test]
     fn model_autoregressive() {
 
         let (cons, lag1, lag2) = (100., 0.5, -0.25);
 
-        let mut y: Array1<f64> = Array::zeros(200) + cons;
-        y[0] = 150.;
-        y[1] = 50.;
-
-        for i in 2..y.len() {
-            y[i] += y[i - 1] * lag1 + y[i - 2] * lag2;
-        }
+        // noise-free, so that the coefficients are recovered exactly from the transition of the zero start
+        let mut model = Model::autoregressive(2);
+        let y = model.simulate(200, &arr1(&[cons, lag1, lag2]), 0., None, Noise::Normal, 0, 0);
 
         let y_train = y.slice(s![..180]).to_owned();
         
         let mut y_test = y.slice(s![180..]).to_owned();
        
-        let mut model = Model::autoregressive(2);
         model.fit(&y_train, None);
         let mut y_preds = model.predict(20, None);
 
@@ -171,16 +775,13 @@ mod tests {
 
         let (cons, lag1, lag2, lag_s, s) = (60., 0.45, -0.35, 0.25, 7);
 
-        let mut y: Array1<f64> = Array::zeros(100) + cons;
-
-        for i in s..y.len() {
-            y[i] += y[i - 1] * lag1 + y[i - 2] * lag2 + y[i - s] * lag_s;
-        }
+        // noise-free, as in model_autoregressive
+        let mut model = ModelBuilder::new().order((2, 0, 0)).seasonal_order((1, 0, 0, s)).seasonality(Seasonality::Additive).build();
+        let y = model.simulate(100, &arr1(&[cons, lag1, lag2, lag_s]), 0., None, Noise::Normal, 0, 0);
 
         let y_train = y.slice(s![..80]).to_owned();
         let mut y_test = y.slice(s![80..]).to_owned();
        
-        let mut model = Model::sarima((2, 0, 0), (1, 0, 0, s));
         model.fit(&y_train, None);
         let mut y_preds = model.predict(20, None);
 
@@ -193,6 +794,237 @@ mod tests {
         assert_eq!(y_test, y_preds);
     }
 
+    #[test]
+    fn model_seasonal_ar_multiplicative() {
+        // (1 - 0.5 B)(1 - 0.4 B^4) y_t = c implies a cross term at lag 5
+        let (cons, lag1, lag_s, s) = (20., 0.5, 0.4, 4);
+
+        let mut y: Array1<f64> = Array::zeros(100) + cons;
+        for (i, v) in [10., 50., 30., 90., 20.].into_iter().enumerate() {
+            y[i] = v;
+        }
+        for i in s + 1..y.len() {
+            y[i] += y[i - 1] * lag1 + y[i - s] * lag_s - y[i - s - 1] * lag1 * lag_s;
+        }
+
+        let mut model = Model::sarima((1, 0, 0), (1, 0, 0, s));
+        model.fit(&y.slice(s![..80]).to_owned(), None);
+        let y_preds = model.predict(20, None);
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[cons, lag1, lag_s]), coefs);
+        assert_eq!(y.slice(s![80..]).mapv(|x| (100. * x).round() / 100.), y_preds.mapv(|x| (100. * x).round() / 100.));
+    }
+
+    #[test]
+    fn model_seasonal_ma_multiplicative() {
+        // y_t = c + (1 + 0.4 B)(1 + 0.3 B^3) e_t
+        let (cons, theta, seasonal_theta) = (5., 0.4, 0.3);
+        let mut model = Model::sarima((0, 0, 1), (0, 0, 1, 3));
+        let y = model.simulate(400, &arr1(&[cons, theta, seasonal_theta]), 0.6, None, Noise::Normal, 1, 50);
+        model.fit(&y, None);
+
+        let coefs = model.coefs.as_ref().unwrap();
+        assert!((coefs[0] - cons).abs() < 0.05);
+        assert!((coefs[1] - theta).abs() < 0.1);
+        assert!((coefs[2] - seasonal_theta).abs() < 0.1);
+    }
+
+    #[test]
+    fn model_fitted_values() {
+        // Δy_t = 0.5 Δy_t-1 + 1 without noise is predicted exactly once two differences are available
+        let mut y: Array1<f64> = Array::zeros(50);
+        y[1] = 3.;
+        for t in 2..y.len() {
+            y[t] = y[t - 1] + 0.5 * (y[t - 1] - y[t - 2]) + 1.;
+        }
+
+        let mut model = Model::arima(1, 1, 0);
+        model.fit(&y, None);
+        let fitted = model.fitted_values();
+        let residuals = model.residuals();
+        assert_eq!(fitted.len(), y.len());
+        assert!(fitted[0].is_nan() && fitted[1].is_nan());
+        assert!(residuals.slice(s![2..]).iter().all(|r| r.abs() < 1e-6));
+        assert!((2..y.len()).all(|t| (fitted[t] + residuals[t] - y[t]).abs() < 1e-9));
+    }
+
+    #[test]
+    fn model_fitted_values_transformed() {
+        let mut model = ModelBuilder::new().order((1, 0, 1)).transform(Transform::Log).build();
+        let y = model.simulate(100, &arr1(&[10., 0., 0.]), 0.6, None, Noise::Normal, 1, 0);
+        model.fit(&y, None);
+        let (fitted, residuals) = (model.fitted_values(), model.residuals());
+        assert!(fitted.slice(s![1..]).iter().all(|&v| v > 0.));
+        assert!((1..100).all(|t| (fitted[t] + residuals[t] - y[t]).abs() < 1e-6 * y[t]));
+    }
+
+    #[test]
+    fn model_predict_range() {
+        let mut model = Model::autoregressive(1);
+        let y = model.simulate(200, &arr1(&[3., 0.6]), 0.5, None, Noise::Normal, 42, 50);
+        model.fit(&y, None);
+        let (c, phi) = (model.coefs.as_ref().unwrap()[0], model.coefs.as_ref().unwrap()[1]);
+
+        // one-step-ahead in sample followed by forecasts past the end
+        let preds = model.predict_range(190, 204, None, None);
+        assert_eq!(preds.slice(s![..10]), model.fitted_values().slice(s![190..]));
+        assert_eq!(preds.slice(s![10..]), model.predict(5, None));
+
+        // from 175 + 5 on, predictions rather than observations feed the lag
+        let preds = model.predict_range(175, 199, Some(5), None);
+        assert_eq!(preds.slice(s![..5]), model.fitted_values().slice(s![175..180]));
+        assert!((preds[5] - (c + phi * y[179])).abs() < 1e-9);
+        assert!((6..25).all(|i| (preds[i] - (c + phi * preds[i - 1])).abs() < 1e-9));
+    }
+
+    #[test]
+    #[should_panic(expected = "should not be after the end of y")]
+    fn model_predict_range_dynamic_after_end() {
+        let mut model = Model::autoregressive(1);
+        model.fit(&model.simulate(50, &arr1(&[0., 0.5]), 1., None, Noise::Normal, 0, 0), None);
+        model.predict_range(40, 54, Some(11), None);
+    }
+
+    #[test]
+    fn model_append() {
+        let mut rng = Rng::new(5);
+        let y = Array::range(0., 200., 1.).mapv(|t: f64| 0.1 * t + (t / 2.).sin() + 2. * rng.normal());
+        let cases = [
+            ModelBuilder::new().order((1, 0, 1)).trend(Trend::Ct).build(),
+            ModelBuilder::new().order((1, 1, 1)).seasonal_orders(vec![(1, 0, 1, 4)]).build(),
+        ];
+        for mut model in cases {
+            model.fit(&y.slice(s![..150]).to_owned(), None);
+            let (coefs, fitted) = (model.coefs.clone(), model.fitted_values());
+            model.append(&y.slice(s![150..]).to_owned(), None, false);
+            assert_eq!(model.coefs, coefs);
+            assert_eq!(model.fitted_values().len(), 200);
+            assert!((0..150).all(|t| fitted[t].is_nan() || (fitted[t] - model.fitted_values()[t]).abs() < 1e-9));
+
+            // forecasts from the last observations match those filtering all of them
+            let windowed = model.predict(10, None);
+            let errors_fit = model.errors_fit.take();
+            let full = model.predict(10, None);
+            model.errors_fit = errors_fit;
+            assert!((0..10).all(|i| (windowed[i] - full[i]).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn model_append_incremental() {
+        // errors continued over the appended observations equal those filtering all of them
+        let x = Array::from_iter((0..300).map(|t| (t as f64 / 5.).sin())).insert_axis(Axis(1));
+        let cases = [
+            (ModelBuilder::new().order((1, 1, 1)).seasonal_order((0, 1, 1, 4)).exog(Exog::Levels).exog_lags(vec![vec![0, 2]]),
+                vec![0.3, -0.4, 0.5, 1., -0.5], Some(&x)),
+            (ModelBuilder::new().order((2, 0, 1)).trend(Trend::Ct).missing(Missing::Skip), vec![1., 0.01, 0.3, 0.5, 0.2], None),
+            (ModelBuilder::new().order((1, 0, 1)).seasonal_order((1, 0, 1, 12)).seasonality(Seasonality::Multiplicative),
+                vec![1., 0.3, 0.4, 0.5, 0.3], None)
+        ];
+        for (i, (builder, params, x)) in cases.into_iter().enumerate() {
+            let mut model = builder.build();
+            let mut y = model.simulate(300, &arr1(&params), 1., x, Noise::Normal, i as u64, 50);
+            if model.missing == Missing::Skip {
+                y[260] = f64::NAN;
+            }
+
+            model.fit(&y.slice(s![..250]).to_owned(), x.map(|x| x.slice(s![..250, ..]).to_owned()).as_ref());
+            model.append(&y.slice(s![250..]).to_owned(), x.map(|x| x.slice(s![250.., ..]).to_owned()).as_ref(), false);
+            let (errors, full) = (model.errors_fit.as_ref().unwrap(), model.filter());
+            assert!((0..300).all(|t| (errors[t].is_nan() && full[t].is_nan()) || (errors[t] - full[t]).abs() < 1e-9), "case {}", i);
+        }
+    }
+
+    #[test]
+    fn model_append_regression_errors() {
+        let n = 120;
+        let x = Array::from_iter((0..n + 5).map(|t| (t as f64 / 3.).sin())).insert_axis(Axis(1));
+        let mut rng = Rng::new(6);
+        let y = x.column(0).mapv(|v| 2. * v + 0.5 * rng.normal());
+        let mut model = ModelBuilder::new().order((1, 0, 1)).regression(Regression::SarimaErrors).build();
+        model.fit(&y.slice(s![..100]).to_owned(), Some(&x.slice(s![..100, ..]).to_owned()));
+        model.append(&y.slice(s![100..n]).to_owned(), Some(&x.slice(s![100..n, ..]).to_owned()), false);
+
+        let x_future = x.slice(s![n.., ..]).to_owned();
+        let windowed = model.predict(5, Some(&x_future));
+        model.errors_fit = None;
+        let full = model.predict(5, Some(&x_future));
+        assert!((0..5).all(|i| (windowed[i] - full[i]).abs() < 1e-9));
+    }
+
+    #[test]
+    fn model_append_refit() {
+        let mut rng = Rng::new(7);
+        let y = Array::from_iter((0..100).map(|_| 20. + 0.5 * rng.normal()));
+        let mut appended = Model::arma(1, 1);
+        appended.fit(&y.slice(s![..80]).to_owned(), None);
+        appended.append(&y.slice(s![80..]).to_owned(), None, true);
+
+        let mut model = Model::arma(1, 1);
+        model.fit(&y, None);
+        assert_eq!(appended.coefs, model.coefs);
+        assert_eq!(appended.predict(5, None), model.predict(5, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "x has 2 columns. It should have 1.")]
+    fn model_apply_exog_columns() {
+        let x = Array::from_shape_fn((50, 1), |(t, _)| (t as f64).sin());
+        let mut model = Model::autoregressive(1);
+        model.fit(&Array::range(0., 50., 1.).mapv(f64::cos), Some(&x));
+        model.apply(&Array::zeros(50), Some(&Array::zeros((50, 2))));
+    }
+
+    #[test]
+    fn model_apply() {
+        let mut parent = Model::arima(1, 1, 0);
+        let y = parent.simulate(300, &arr1(&[1., 0.5]), 0.5, None, Noise::Normal, 8, 0);
+        parent.fit(&y.slice(s![..250]).to_owned(), None);
+
+        // a shorter series at a different level with the same dynamics
+        let child_y = y.slice(s![260..]).mapv(|v| v + 1000.);
+        let child = parent.apply(&child_y, None);
+        assert_eq!(child.coefs, parent.coefs);
+
+        let (c, phi) = (parent.coefs.as_ref().unwrap()[0], parent.coefs.as_ref().unwrap()[1]);
+        let preds = child.predict(2, None);
+        let first = child_y[39] + c + phi * (child_y[39] - child_y[38]);
+        assert!((preds[0] - first).abs() < 1e-9);
+        assert!((preds[1] - (first + c + phi * (first - child_y[39]))).abs() < 1e-9);
+        assert!((2..40).all(|t| (child.fitted_values()[t] - (child_y[t - 1] + c + phi * (child_y[t - 1] - child_y[t - 2]))).abs() < 1e-9));
+    }
+
+    #[test]
+    fn model_future_errors() {
+        // y_t = c + e_t + 0.6 e_t-1 has no memory beyond one step, so later forecasts are the mean
+        let mut model = Model::moving_average(1);
+        let y = model.simulate(300, &arr1(&[2., 0.6]), 0.5, None, Noise::Normal, 9, 0);
+        model.fit(&y, None);
+        let y_preds = model.predict(5, None);
+        let cons = model.coefs.as_ref().unwrap()[0];
+        assert!(y_preds.slice(s![1..]).iter().all(|&p| (p - cons).abs() < 1e-12));
+        assert!((y_preds[0] - cons).abs() > 1e-6);
+
+        let mut model = ModelBuilder::new().order((0, 0, 1)).future_errors(FutureErrors::Autoregressive).build();
+        model.fit(&y, None);
+        let y_preds = model.predict(5, None);
+        let cons = model.coefs.as_ref().unwrap()[0];
+        assert!(y_preds.slice(s![1..]).iter().all(|&p| (p - cons).abs() > 1e-12));
+    }
+
+    #[test]
+    fn model_future_errors_autoregressive_baseline() {
+        let spec = ModelBuilder::new().order((1, 0, 1)).future_errors(FutureErrors::Autoregressive);
+        let y = spec.clone().build().simulate(200, &arr1(&[1., 0.5, 0.4]), 1., None, Noise::Normal, 3, 50);
+        let mut model = spec.build();
+        model.fit(&y, None);
+        // forecasts of the heuristic when the errors it is fit to were last changed, so that changes are noticed
+        let y_preds = model.predict(4, None);
+        let expected = arr1(&[1.8548092357056203, 1.8254612158835015, 1.796977742361979, 1.7788200910747558]);
+        assert!((&y_preds - &expected).iter().all(|d| d.abs() < 1e-6), "{:?}", y_preds);
+    }
+
     #[test]
     fn model_exog() {
         let n_rows = 100;
@@ -225,6 +1057,373 @@ mod tests {
         assert_eq!(y_test, y_preds);
     }
 
+    #[test]
+    fn model_hannan_rissanen() {
+        let (cons, lag1, lag2) = (10., 1.2, -0.9);
+        let mut y: Array1<f64> = Array::zeros(200) + cons;
+        y[0] = 150.;
+        y[1] = 50.;
+        for i in 2..y.len() {
+            y[i] += y[i - 1] * lag1 + y[i - 2] * lag2;
+        }
+
+        let mut model = ModelBuilder::new().order((2, 0, 0)).method(Method::HannanRissanen).build();
+        model.fit(&y, None);
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[cons, lag1, lag2]), coefs);
+    }
+
+    #[test]
+    fn model_trend_constant_and_time() {
+        let (cons, slope) = (5., 2.);
+        let y: Array1<f64> = Array::range(1., 101., 1.).mapv(|t| cons + slope * t);
+        let y_train = y.slice(s![..80]).to_owned();
+        let y_test = y.slice(s![80..]).to_owned();
+
+        let mut model = ModelBuilder::new().trend(Trend::Ct).build();
+        model.fit(&y_train, None);
+        let y_preds = model.predict(20, None).mapv(|x| (100. * x).round() / 100.);
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[cons, slope]), coefs);
+        assert_eq!(y_test, y_preds);
+    }
+
+    #[test]
+    fn model_drift_one_difference() {
+        // y = a + b * t: a constant in the differenced model is a drift b in levels
+        let (a, b) = (20., 1.5);
+        let y: Array1<f64> = Array::range(0., 60., 1.).mapv(|t| a + b * t);
+        let y_train = y.slice(s![..50]).to_owned();
+        let y_test = y.slice(s![50..]).to_owned();
+
+        let mut model = Model::arima(0, 1, 0);
+        model.fit(&y_train, None);
+        let y_preds = model.predict(10, None).mapv(|x| (100. * x).round() / 100.);
+
+        assert_eq!(arr1(&[b]), model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.));
+        assert_eq!(y_test, y_preds);
+    }
+
+    #[test]
+    fn model_drift_seasonal_difference() {
+        // y = b * t + seasonal pattern: a constant in the seasonally differenced model is s * b
+        let (b, s) = (0.5, 4);
+        let pattern = arr1(&[3., -1., 0., -2.]);
+        let y: Array1<f64> = Array::from_iter((0..60).map(|t| b * t as f64 + pattern[t % s]));
+        let y_train = y.slice(s![..48]).to_owned();
+        let y_test = y.slice(s![48..]).to_owned();
+
+        let mut model = Model::sarima((0, 0, 0), (0, 1, 0, s));
+        model.fit(&y_train, None);
+        let y_preds = model.predict(12, None).mapv(|x| (100. * x).round() / 100.);
+
+        assert_eq!(arr1(&[b * s as f64]), model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.));
+        assert_eq!(y_test, y_preds);
+    }
+
+    #[test]
+    fn model_quadratic_trend_two_differences() {
+        // y = a + b * t + c * t^2: a constant in the twice differenced model is 2c
+        let (a, b, c) = (3., -2., 0.25);
+        let y: Array1<f64> = Array::range(0., 60., 1.).mapv(|t| a + b * t + c * t * t);
+        let y_train = y.slice(s![..50]).to_owned();
+        let y_test = y.slice(s![50..]).to_owned();
+
+        let mut model = ModelBuilder::new().order((0, 2, 0)).trend(Trend::C).allow_high_order_trend(true).build();
+        model.fit(&y_train, None);
+        let y_preds = model.predict(10, None).mapv(|x| (100. * x).round() / 100.);
+
+        assert_eq!(arr1(&[2. * c]), model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.));
+        assert_eq!(y_test, y_preds);
+    }
+
+    #[test]
+    fn model_trend_none() {
+        let lag1 = 0.8;
+        let mut y: Array1<f64> = Array::zeros(100);
+        y[0] = 100.;
+        for i in 1..y.len() {
+            y[i] = y[i - 1] * lag1;
+        }
+
+        let mut model = ModelBuilder::new().order((1, 0, 0)).trend(Trend::N).build();
+        model.fit(&y, None);
+        model.predict(5, None);
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[lag1]), coefs);
+        assert_eq!(vec!["ar.L1"], model.coef_names());
+    }
+
+    #[test]
+    fn model_missing_skip() {
+        let (cons, lag1, lag2) = (10., 1.2, -0.9);
+        let mut y: Array1<f64> = Array::zeros(200) + cons;
+        y[0] = 150.;
+        y[1] = 50.;
+        for i in 2..y.len() {
+            y[i] += y[i - 1] * lag1 + y[i - 2] * lag2;
+        }
+        let mut y_train = y.slice(s![..180]).to_owned();
+        let y_test = y.slice(s![180..]).mapv(|x| (100. * x).round() / 100.);
+        for i in [50, 51, 120, 179] {
+            y_train[i] = f64::NAN;
+        }
+
+        for method in [Method::Recursive, Method::HannanRissanen] {
+            let mut model = ModelBuilder::new().order((2, 0, 0)).method(method).missing(Missing::Skip).build();
+            model.fit(&y_train, None);
+            let y_preds = model.predict(20, None).mapv(|x| (100. * x).round() / 100.);
+
+            let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+            assert_eq!(arr1(&[cons, lag1, lag2]), coefs);
+            assert_eq!(y_test, y_preds);
+        }
+    }
+
+    #[test]
+    fn model_missing_skip_differenced() {
+        let (drift, lag1) = (2., 0.6);
+        let mut y_diff: Array1<f64> = Array::zeros(120) + drift;
+        y_diff[0] = 30.;
+        for i in 1..y_diff.len() {
+            y_diff[i] += y_diff[i - 1] * lag1;
+        }
+        let mut y = y_diff.clone();
+        y.accumulate_axis_inplace(Axis(0), |&prev, cur| *cur += prev);
+
+        let mut y_train = y.slice(s![..100]).to_owned();
+        let y_test = y.slice(s![100..]).mapv(|x| (100. * x).round() / 100.);
+        y_train[40] = f64::NAN;
+        y_train[98] = f64::NAN;
+
+        let mut model = ModelBuilder::new().order((1, 1, 0)).missing(Missing::Skip).build();
+        model.fit(&y_train, None);
+        let y_preds = model.predict(20, None).mapv(|x| (100. * x).round() / 100.);
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[drift, lag1]), coefs);
+        assert_eq!(y_test, y_preds);
+    }
+
+    #[test]
+    #[should_panic(expected = "y contains missing values")]
+    fn model_missing_raise() {
+        let mut y: Array1<f64> = Array::ones(50);
+        y[10] = f64::NAN;
+        Model::autoregressive(1).fit(&y, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The first 1 observations are lost to differencing")]
+    fn model_missing_first_levels() {
+        let mut y: Array1<f64> = Array::range(0., 50., 1.);
+        y[0] = f64::NAN;
+        ModelBuilder::new().order((1, 1, 0)).missing(Missing::Skip).build().fit(&y, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "y is missing (NaN) at 10 and so is x column 0")]
+    fn model_missing_y_and_x() {
+        let mut y: Array1<f64> = Array::range(0., 50., 1.);
+        let mut x: Array2<f64> = Array::ones((50, 1));
+        y[10] = f64::NAN;
+        x[[10, 0]] = f64::NAN;
+        ModelBuilder::new().order((1, 0, 0)).missing(Missing::Skip).build().fit(&y, Some(&x));
+    }
+
+    #[test]
+    #[should_panic(expected = "x used for predict contains missing values")]
+    fn model_missing_future_exog() {
+        let y: Array1<f64> = Array::range(0., 50., 1.);
+        let x: Array2<f64> = Array::ones((50, 1));
+        let mut x_future: Array2<f64> = Array::ones((5, 1));
+        x_future[[2, 0]] = f64::NAN;
+
+        let mut model = ModelBuilder::new().missing(Missing::Skip).build();
+        model.fit(&y, Some(&x));
+        model.predict(5, Some(&x_future));
+    }
+
+    #[test]
+    fn model_log_transform() {
+        // exponential growth is a drift after a log transformation
+        let (level, growth): (f64, f64) = (5., 1.03);
+        let y: Array1<f64> = Array::range(0., 60., 1.).mapv(|t| level * growth.powf(t));
+        let y_train = y.slice(s![..50]).to_owned();
+        let y_test = y.slice(s![50..]).mapv(|x| (100. * x).round() / 100.);
+
+        let mut model = ModelBuilder::new().order((0, 1, 0)).transform(Transform::Log).build();
+        model.fit(&y_train, None);
+        let y_preds = model.predict(10, None).mapv(|x| (100. * x).round() / 100.);
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (1e4 * x).round() / 1e4);
+        assert_eq!(arr1(&[(growth.ln() * 1e4).round() / 1e4]), coefs);
+        assert_eq!(y_test, y_preds);
+    }
+
+    #[test]
+    fn model_bias_adjust() {
+        let mut rng = Rng::new(10);
+        let y = Array::from_iter((0..100).map(|t| (t as f64 / 20. + 0.05 * rng.normal()).exp()));
+
+        let mut median = ModelBuilder::new().order((0, 1, 0)).transform(Transform::Log).build();
+        median.fit(&y, None);
+        let y_median = median.predict(5, None);
+
+        let mut mean = ModelBuilder::new().order((0, 1, 0)).transform(Transform::Log).bias_adjust(true).build();
+        mean.fit(&y, None);
+        let y_mean = mean.predict(5, None);
+
+        let adjustment = mean.forecast_variance(5).mapv(|v| 1. + v / 2.);
+        assert!(y_mean.iter().zip(y_median.iter()).all(|(a, b)| a > b));
+        assert_eq!((&y_mean / &y_median).mapv(|x| (1e9 * x).round()), adjustment.mapv(|x| (1e9 * x).round()));
+    }
+
+    #[test]
+    fn model_predict_interval_random_walk() {
+        // the forecast variance of a random walk grows linearly with the horizon
+        let mut model = ModelBuilder::new().order((0, 1, 0)).trend(Trend::N).build();
+        let y = model.simulate(200, &Array::zeros(0), 0.5, None, Noise::Normal, 11, 0);
+        model.fit(&y, None);
+        let y_preds = model.predict(4, None);
+        let (lower, upper) = model.predict_interval(4, None, 0.95);
+
+        let sigma = model.sigma2.unwrap().sqrt();
+        let half_width = (&upper - &lower) / 2.;
+        let expected = Array::range(1., 5., 1.).mapv(|h: f64| 1.959964 * sigma * h.sqrt());
+
+        assert_eq!(half_width.mapv(|x| (1e4 * x).round()), expected.mapv(|x| (1e4 * x).round()));
+        assert_eq!(((&upper + &lower) / 2.).mapv(|x| (1e9 * x).round()), y_preds.mapv(|x| (1e9 * x).round()));
+    }
+
+    #[test]
+    fn model_predict_interval_log_transform() {
+        let mut rng = Rng::new(12);
+        let y = Array::from_iter((0..100).map(|t| (t as f64 / 20. + 0.05 * rng.normal()).exp()));
+        let mut model = ModelBuilder::new().order((1, 1, 0)).transform(Transform::Log).build();
+        model.fit(&y, None);
+        let y_preds = model.predict(6, None);
+        let (lower, upper) = model.predict_interval(6, None, 0.8);
+
+        // intervals are transformed back, so they are asymmetric around the median forecast
+        assert!((0..6).all(|i| lower[i] < y_preds[i] && y_preds[i] < upper[i]));
+        assert!((0..6).all(|i| upper[i] - y_preds[i] > y_preds[i] - lower[i]));
+    }
+
+    #[test]
+    fn model_coef_names() {
+        let mut model = Model::sarima((2, 1, 1), (1, 0, 1, 7));
+        model.fit(&Array::zeros(50), Some(&Array::zeros((50, 2))));
+        let names = vec!["intercept", "ma.L1", "ma.S.L7", "ar.L1", "ar.L2", "ar.S.L7", "x0", "x1"];
+        assert_eq!(names, model.coef_names());
+    }
+
+    #[test]
+    fn model_exog_lags() {
+        // marketing spend affects sales two periods later, y_t = 5 + 3 x_{t-2} + 0.5 x_{t-3}
+        let n = 60;
+        let x = Array::range(0., (n + 5) as f64, 1.).mapv(|t| (t / 2.).sin() * 3. + (t as usize % 4) as f64);
+        let mut y: Array1<f64> = Array::zeros(n + 5) + 5.;
+        for t in 3..n + 5 {
+            y[t] += 3. * x[t - 2] + 0.5 * x[t - 3];
+        }
+        let x = x.insert_axis(Axis(1));
+
+        let mut model = ModelBuilder::new().exog_lags(vec![vec![2, 3]]).build();
+        model.fit(&y.slice(s![..n]).to_owned(), Some(&x.slice(s![..n, ..]).to_owned()));
+        assert_eq!(vec!["intercept", "x0.L2", "x0.L3"], model.coef_names());
+
+        // only the first 3 of 5 future values are used
+        let y_preds = model.predict(5, Some(&x.slice(s![n..n + 3, ..]).to_owned()));
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[5., 3., 0.5]), coefs);
+        assert_eq!(y.slice(s![n..]).mapv(|x| (100. * x).round() / 100.), y_preds.mapv(|x| (100. * x).round() / 100.));
+    }
+
+    #[test]
+    #[should_panic(expected = "It should be between length: 3 and 5")]
+    fn model_exog_lags_future_too_short() {
+        let mut model = ModelBuilder::new().exog_lags(vec![vec![2], vec![3]]).build();
+        model.fit(&Array::range(0., 30., 1.), Some(&Array::ones((30, 2))));
+        model.predict(5, Some(&Array::ones((2, 2))));
+    }
+
+    #[test]
+    fn model_multiple_seasonal_periods() {
+        let (cons, lag_3, lag_5) = (20., 0.4, 0.3);
+        let mut y: Array1<f64> = Array::zeros(120) + cons;
+        for (i, v) in [5., 9., 2., 7., 4.].into_iter().enumerate() {
+            y[i] = v;
+        }
+        for i in 5..y.len() {
+            y[i] += y[i - 3] * lag_3 + y[i - 5] * lag_5;
+        }
+
+        let mut model = ModelBuilder::new()
+            .seasonal_orders(vec![(1, 0, 0, 3), (1, 0, 0, 5)])
+            .seasonality(Seasonality::Additive)
+            .build();
+        model.fit(&y.slice(s![..100]).to_owned(), None);
+        let y_preds = model.predict(20, None);
+
+        assert_eq!(vec!["intercept", "ar.S.L3", "ar.S.L5"], model.coef_names());
+        assert_eq!("SARIMAX(0,0,0)(1,0,0,3)(1,0,0,5)", model.to_string());
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[cons, lag_3, lag_5]), coefs);
+        assert_eq!(y.slice(s![100..]).mapv(|x| (100. * x).round() / 100.), y_preds.mapv(|x| (100. * x).round() / 100.));
+    }
+
+    #[test]
+    fn model_multiple_seasonal_differences() {
+        // deterministic cycles of period 3 and 4 are removed by seasonal differences of both
+        let y = Array::range(0., 60., 1.).mapv(|t| 50. + [0., 3., 1.][t as usize % 3] + [2., 0., 1., 5.][t as usize % 4]);
+        let mut model = ModelBuilder::new().seasonal_orders(vec![(0, 1, 0, 3), (0, 1, 0, 4)]).build();
+        model.fit(&y.slice(s![..48]).to_owned(), None);
+        let y_preds = model.predict(12, None);
+        assert_eq!(y.slice(s![48..]).mapv(|x| (100. * x).round() / 100.), y_preds.mapv(|x| (100. * x).round() / 100.));
+    }
+
+    #[test]
+    fn model_display() {
+        let model = Model::sarima((2, 1, 1), (1, 0, 1, 7));
+        assert_eq!("SARIMAX(2,1,1)(1,0,1,7)", model.to_string());
+    }
+
+    #[test]
+    fn model_exog_columns_levels_and_differenced() {
+        // Δy = 2 Δx0 + 3 x1: price enters as its difference, promotion dummy in levels
+        let n = 60;
+        let x0 = Array::range(0., n as f64, 1.).mapv(|t| 10. + (t / 3.).sin() * 4.);
+        let x1 = Array::from_iter((0..n).map(|t| if t % 7 == 0 {1.} else {0.}));
+        let mut x: Array2<f64> = Array::zeros((n, 2));
+        x.column_mut(0).assign(&x0);
+        x.column_mut(1).assign(&x1);
+
+        let mut y: Array1<f64> = Array::zeros(n);
+        y[0] = 100.;
+        for t in 1..n {
+            y[t] = y[t - 1] + 2. * (x0[t] - x0[t - 1]) + 3. * x1[t];
+        }
+
+        let mut model = ModelBuilder::new().order((0, 1, 0)).trend(Trend::N).exog(Exog::Columns(vec![true, false])).build();
+        model.fit(&y.slice(s![..50]).to_owned(), Some(&x.slice(s![..50, ..]).to_owned()));
+        let y_preds = model.predict(10, Some(&x.slice(s![50.., ..]).to_owned()));
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|x| (100. * x).round() / 100.);
+        assert_eq!(arr1(&[2., 3.]), coefs);
+        assert_eq!(y.slice(s![50..]).mapv(|x| (100. * x).round() / 100.), y_preds.mapv(|x| (100. * x).round() / 100.));
+    }
+
+    #[test]
+    #[should_panic(expected = "Differencing is set for 1 exog columns but x has 2")]
+    fn model_exog_columns_wrong_length() {
+        let mut model = ModelBuilder::new().exog(Exog::Columns(vec![true])).build();
+        model.fit(&Array::zeros(20), Some(&Array::zeros((20, 2))));
+    }
+
     #[test]
     #[should_panic(expected = "to be set to 1")]
     fn model_new_seasonal_s_equal_one() {
@@ -235,34 +1434,34 @@ mod tests {
     fn model_new_sarima() {
         let model = Model::sarima((1, 2, 3), (4, 5, 6, 7));
         assert_eq!(model.order, Order {p: 1, d: 2, q: 3, s: 1});
-        assert_eq!(model.seasonal_order, Order {p: 4, d: 5, q: 6, s: 7});
+        assert_eq!(model.seasonal_orders, vec![Order {p: 4, d: 5, q: 6, s: 7}]);
     }
 
     #[test]
     fn model_new_arima() {
         let model = Model::arima(1, 2, 3);
         assert_eq!(model.order, Order {p: 1, d: 2, q: 3, s: 1});
-        assert_eq!(model.seasonal_order, Order {p: 0, d: 0, q: 0, s: 0});
+        assert_eq!(model.seasonal_orders, vec![Order {p: 0, d: 0, q: 0, s: 0}]);
     }
 
     #[test]
     fn model_new_arma() {
         let model = Model::arma(1, 3);
         assert_eq!(model.order, Order {p: 1, d: 0, q: 3, s: 1});
-        assert_eq!(model.seasonal_order, Order {p: 0, d: 0, q: 0, s: 0});
+        assert_eq!(model.seasonal_orders, vec![Order {p: 0, d: 0, q: 0, s: 0}]);
     }
 
     #[test]
     fn model_new_ar() {
         let model = Model::autoregressive(1);
         assert_eq!(model.order, Order {p: 1, d: 0, q: 0, s: 1});
-        assert_eq!(model.seasonal_order, Order {p: 0, d: 0, q: 0, s: 0});
+        assert_eq!(model.seasonal_orders, vec![Order {p: 0, d: 0, q: 0, s: 0}]);
     }
 
     #[test]
     fn new_ma() {
         let model = Model::moving_average(3);
         assert_eq!(model.order, Order {p: 0, d: 0, q: 3, s: 1});
-        assert_eq!(model.seasonal_order, Order {p: 0, d: 0, q: 0, s: 0});
+        assert_eq!(model.seasonal_orders, vec![Order {p: 0, d: 0, q: 0, s: 0}]);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,335 @@
+use super::{Exog, FutureErrors, Method, Missing, Model, Order, Regression, Seasonality, Transform, Trend};
+
+
+/// Configure and validate a [Model] specification.
+///
+/// ```ignore
+/// let model = ModelBuilder::new()
+///     .order((1, 1, 1))
+///     .seasonal_orders(vec![(1, 0, 1, 24), (1, 0, 1, 168)])
+///     .trend(Trend::N)
+///     .method(Method::HannanRissanen)
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ModelBuilder {
+    order: (usize, usize, usize),
+    seasonal_orders: Vec<(usize, usize, usize, usize)>,
+    seasonality: Seasonality,
+    trend: Option<Trend>,
+    method: Method,
+    future_errors: FutureErrors,
+    exog: Exog,
+    regression: Regression,
+    exog_lags: Vec<Vec<usize>>,
+    missing: Missing,
+    transform: Transform,
+    bias_adjust: bool,
+    enforce_stationarity: bool,
+    enforce_invertibility: bool,
+    allow_high_order_trend: bool
+}
+
+impl Default for ModelBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModelBuilder {
+    /// defaults to a multiplicative SARIMA(0,0,0)(0,0,0,0) with the default trend, recursive estimation,
+    /// zero future innovations, differenced exogenous variables in an ARMAX equation, no missing values allowed and no transformation
+    pub fn new() -> Self {
+        Self {
+            order: (0, 0, 0),
+            seasonal_orders: vec![(0, 0, 0, 0)],
+            seasonality: Seasonality::Multiplicative,
+            trend: None,
+            method: Method::Recursive,
+            future_errors: FutureErrors::Zero,
+            exog: Exog::Differenced,
+            regression: Regression::Armax,
+            exog_lags: Vec::new(),
+            missing: Missing::Raise,
+            transform: Transform::None,
+            bias_adjust: false,
+            enforce_stationarity: false,
+            enforce_invertibility: false,
+            allow_high_order_trend: false
+        }
+    }
+
+    /// (p, d, q)
+    pub fn order(mut self, order: (usize, usize, usize)) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// (P, D, Q, s) of a single seasonal period
+    pub fn seasonal_order(mut self, seasonal_order: (usize, usize, usize, usize)) -> Self {
+        self.seasonal_orders = vec![seasonal_order];
+        self
+    }
+
+    /// (P, D, Q, s) for each seasonal period, e.g. daily and weekly cycles of hourly data
+    pub fn seasonal_orders(mut self, seasonal_orders: Vec<(usize, usize, usize, usize)>) -> Self {
+        self.seasonal_orders = seasonal_orders;
+        self
+    }
+
+    /// multiplicative (default) or additive seasonal AR and MA polynomials
+    pub fn seasonality(mut self, seasonality: Seasonality) -> Self {
+        self.seasonality = seasonality;
+        self
+    }
+
+    /// defaults to a constant when d + D < 2 (a mean, or a drift in levels when d + D = 1) and no trend otherwise
+    pub fn trend(mut self, trend: Trend) -> Self {
+        self.trend = Some(trend);
+        self
+    }
+
+    /// allow trends that integrate to a polynomial of degree 2 or more in levels,
+    /// e.g. a constant with d = 2 becomes a quadratic trend
+    pub fn allow_high_order_trend(mut self, allow: bool) -> Self {
+        self.allow_high_order_trend = allow;
+        self
+    }
+
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// zero (default) or autoregressive forecasts of future MA innovations
+    pub fn future_errors(mut self, future_errors: FutureErrors) -> Self {
+        self.future_errors = future_errors;
+        self
+    }
+
+    pub fn exog(mut self, exog: Exog) -> Self {
+        self.exog = exog;
+        self
+    }
+
+    /// lags of each exogenous variable, 0 for the contemporaneous value, e.g. `vec![vec![0, 1, 2], vec![1]]`
+    /// for a distributed lag of the first variable and the previous value of the second.
+    pub fn exog_lags(mut self, exog_lags: Vec<Vec<usize>>) -> Self {
+        self.exog_lags = exog_lags;
+        self
+    }
+
+    /// ARMAX equation or regression with SARIMA errors
+    pub fn regression(mut self, regression: Regression) -> Self {
+        self.regression = regression;
+        self
+    }
+
+    pub fn missing(mut self, missing: Missing) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    pub fn transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// back-transform predictions to the mean rather than the median of the forecast distribution
+    pub fn bias_adjust(mut self, bias_adjust: bool) -> Self {
+        self.bias_adjust = bias_adjust;
+        self
+    }
+
+    /// estimate AR coefficients with each factor of the AR polynomial constrained to be stationary
+    pub fn enforce_stationarity(mut self, enforce: bool) -> Self {
+        self.enforce_stationarity = enforce;
+        self
+    }
+
+    /// estimate MA coefficients with each factor of the MA polynomial constrained to be invertible
+    pub fn enforce_invertibility(mut self, enforce: bool) -> Self {
+        self.enforce_invertibility = enforce;
+        self
+    }
+
+    /// returns a description of the first invalid setting found
+    pub fn validate(&self) -> Result<(), String> {
+        for &(p, d, q, s) in self.seasonal_orders.iter() {
+            if s == 1 {
+                return Err("It doesn't make sense for periodicity (s) to be set to 1.".to_string());
+            }
+            if s == 0 && p + d + q > 0 {
+                return Err("Periodicity (s) must be set when the seasonal order has AR, I or MA terms.".to_string());
+            }
+        }
+
+        if self.regression == Regression::SarimaErrors && self.exog != Exog::Differenced {
+            return Err("Regression with SARIMA errors is in levels and differences exog along with y, so exog must be differenced.".to_string());
+        }
+
+        if self.exog_lags.iter().any(|lags| lags.is_empty()) {
+            return Err("Each exog column needs at least one lag, 0 for the contemporaneous value.".to_string());
+        }
+        if self.regression == Regression::SarimaErrors && self.exog_lags.iter().flatten().any(|&lag| lag > 0) {
+            return Err("Exog lags are not supported with regression with SARIMA errors. Add lagged columns to x instead.".to_string());
+        }
+
+        if self.seasonality == Seasonality::Additive {
+            // the sum of stationary (or invertible) factors need not be stationary (or invertible)
+            let ar_factors = (self.order.0 > 0) as usize + self.seasonal_orders.iter().filter(|so| so.0 > 0).count();
+            let ma_factors = (self.order.2 > 0) as usize + self.seasonal_orders.iter().filter(|so| so.2 > 0).count();
+            if (self.enforce_stationarity && ar_factors > 1) || (self.enforce_invertibility && ma_factors > 1) {
+                return Err("Enforcing stationarity or invertibility with seasonal terms requires multiplicative seasonality.".to_string());
+            }
+        }
+
+        let n_diff = self.n_diff();
+        if let Some(degree) = self.resolved_trend().degree() {
+            if degree + n_diff >= 2 && !self.allow_high_order_trend {
+                return Err(format!(
+                    "Trend {:?} with d + D = {} is a polynomial of degree {} in levels. Use allow_high_order_trend to include it anyway.",
+                    self.resolved_trend(), n_diff, degree + n_diff
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn resolved_trend(&self) -> Trend {
+        self.trend.unwrap_or(if self.n_diff() < 2 {Trend::C} else {Trend::N})
+    }
+
+    /// d + D of all seasonal periods
+    fn n_diff(&self) -> usize {
+        self.order.1 + self.seasonal_orders.iter().map(|so| so.1).sum::<usize>()
+    }
+
+    pub fn build(self) -> Model {
+        if let Err(e) = self.validate() {panic!("{}", e)}
+
+        let (p, d, q) = self.order;
+        let order = Order {p, d, q, s: 1};
+
+        let seasonal_orders = self.seasonal_orders.iter().map(|&(p, d, q, s)| Order {p, d, q, s}).collect();
+
+        Model {
+            order,
+            seasonal_orders,
+            seasonality: self.seasonality,
+            trend: self.resolved_trend(),
+            method: self.method,
+            future_errors: self.future_errors,
+            exog: self.exog,
+            regression: self.regression,
+            exog_lags: self.exog_lags,
+            missing: self.missing,
+            transform: self.transform,
+            bias_adjust: self.bias_adjust,
+            enforce_stationarity: self.enforce_stationarity,
+            enforce_invertibility: self.enforce_invertibility,
+            lambda: None,
+            endog_fit: None,
+            exog_fit: None,
+            coefs: None,
+            sigma2: None,
+            errors_fit: None,
+            nobs: None
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_defaults() {
+        let model = ModelBuilder::new().order((1, 2, 3)).seasonal_order((4, 5, 6, 7)).build();
+        assert_eq!(model.order, Order {p: 1, d: 2, q: 3, s: 1});
+        assert_eq!(model.seasonal_orders, vec![Order {p: 4, d: 5, q: 6, s: 7}]);
+        assert_eq!(model.trend, Trend::N);
+        assert_eq!(model.method, Method::Recursive);
+        assert_eq!(model.future_errors, FutureErrors::Zero);
+        assert_eq!(model.exog, Exog::Differenced);
+        assert_eq!(model.regression, Regression::Armax);
+        assert_eq!(model.seasonality, Seasonality::Multiplicative);
+    }
+
+    #[test]
+    fn builder_options() {
+        let model = ModelBuilder::new()
+            .trend("ct".parse().unwrap())
+            .method("hannan-rissanen".parse().unwrap())
+            .exog("levels".parse().unwrap())
+            .build();
+        let regression = ModelBuilder::new().regression("sarima-errors".parse().unwrap()).build().regression;
+        assert_eq!(model.trend, Trend::Ct);
+        assert_eq!(model.method, Method::HannanRissanen);
+        assert_eq!(model.exog, Exog::Levels);
+        assert_eq!(regression, Regression::SarimaErrors);
+    }
+
+    #[test]
+    fn builder_seasonal_orders() {
+        let model = ModelBuilder::new().seasonal_orders(vec![(1, 0, 1, 24), (1, 1, 0, 168)]).build();
+        assert_eq!(model.seasonal_orders, vec![Order {p: 1, d: 0, q: 1, s: 24}, Order {p: 1, d: 1, q: 0, s: 168}]);
+        assert_eq!(model.trend, Trend::C);
+    }
+
+    #[test]
+    #[should_panic(expected = "Periodicity (s) must be set")]
+    fn builder_seasonal_terms_without_periodicity() {
+        ModelBuilder::new().seasonal_order((1, 0, 0, 0)).build();
+    }
+
+    #[test]
+    fn builder_default_trend() {
+        assert_eq!(ModelBuilder::new().order((1, 0, 1)).build().trend, Trend::C);
+        assert_eq!(ModelBuilder::new().order((1, 1, 1)).build().trend, Trend::C);
+        assert_eq!(ModelBuilder::new().order((1, 1, 1)).seasonal_order((0, 1, 0, 7)).build().trend, Trend::N);
+        assert_eq!(ModelBuilder::new().order((1, 2, 1)).build().trend, Trend::N);
+    }
+
+    #[test]
+    #[should_panic(expected = "is a polynomial of degree 2 in levels")]
+    fn builder_constant_with_two_differences() {
+        ModelBuilder::new().order((0, 2, 0)).trend(Trend::C).build();
+    }
+
+    #[test]
+    #[should_panic(expected = "is a polynomial of degree 2 in levels")]
+    fn builder_time_trend_with_one_difference() {
+        ModelBuilder::new().order((0, 1, 0)).trend(Trend::T).build();
+    }
+
+    #[test]
+    fn builder_allow_high_order_trend() {
+        let model = ModelBuilder::new().order((0, 2, 0)).trend(Trend::C).allow_high_order_trend(true).build();
+        assert_eq!(model.trend, Trend::C);
+    }
+
+    #[test]
+    #[should_panic(expected = "so exog must be differenced")]
+    fn builder_sarima_errors_with_levels() {
+        ModelBuilder::new().regression(Regression::SarimaErrors).exog(Exog::Levels).build();
+    }
+
+    #[test]
+    #[should_panic(expected = "needs at least one lag")]
+    fn builder_exog_without_lags() {
+        ModelBuilder::new().exog_lags(vec![vec![0], vec![]]).build();
+    }
+
+    #[test]
+    #[should_panic(expected = "requires multiplicative seasonality")]
+    fn builder_enforce_stationarity_additive() {
+        ModelBuilder::new().order((1, 0, 0)).seasonal_order((1, 0, 0, 4)).seasonality(Seasonality::Additive).enforce_stationarity(true).build();
+    }
+
+    #[test]
+    fn builder_unknown_trend() {
+        assert!("x".parse::<Trend>().is_err());
+    }
+}
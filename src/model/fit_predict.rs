@@ -1,74 +1,181 @@
 pub(crate) mod normal_equation;
-use numpy::ndarray::{Array, Array1, Array2, Axis, concatenate, s};
-use super::Model;
+mod css;
+use numpy::ndarray::{Array, Array1, Array2, ArrayView1, ArrayView2, Axis, concatenate, s};
+use super::{FutureErrors, Method, Model, ModelBuilder};
 
 impl Model {
-    pub(super) fn fit_predict_internal(
-        &self,
-        h: usize,
-        mut y: &mut Array1<f64>,
-        mut x: &mut Array2<f64>,
-        exog: &Array2<f64>
-    ) -> (Array1<f64>, Array1<f64>) {
+    /// estimates the coefficients on the prepared data
+    ///
+    /// returns the coefficients, the one-step-ahead errors with them (NaN where there is no prediction
+    /// or y is missing), their variance and the number of observations with an error
+    pub(super) fn fit_coefs(&self, y: &Array1<f64>, x: &mut Array2<f64>) -> (Array1<f64>, Array1<f64>, f64, usize) {
+        let mut coefs = self.fit_internal(&mut y.to_owned(), x);
+        if self.uses_css() {
+            coefs = self.fit_css(y, x, &coefs);
+        }
+        let errors = self.one_step_errors(y, x, &coefs);
 
-        let (coefs, errors) = self.fit_internal(h, &y, &mut x);
+        let nobs = errors.iter().filter(|e| !e.is_nan()).count();
+        let dof = nobs.saturating_sub(coefs.len()).max(1);
+        let sigma2 = errors.iter().filter(|e| !e.is_nan()).map(|e| e * e).sum::<f64>() / dof as f64;
+        (coefs, errors, sigma2, nobs)
+    }
 
-        let new_errors = self.forecast_errors(h, &errors, &exog);
-        
-        let y_preds = self.predict_internal(h, &mut y, &mut x, &coefs, &new_errors);
-        (y_preds, coefs)
+    /// one-step-ahead errors of the prepared data with fixed coefficients, NaN without a prediction
+    pub(super) fn one_step_errors(&self, y: &Array1<f64>, x: &mut Array2<f64>, coefs: &Array1<f64>) -> Array1<f64> {
+        self.continue_errors(&Array::zeros(0), &mut y.to_owned(), x, coefs)
+    }
+
+    /// one-step-ahead errors of the prepared data with fixed coefficients after the known errors of its first rows,
+    /// which must not have missing values, NaN without a prediction
+    pub(super) fn continue_errors(&self, known: &Array1<f64>, y: &mut Array1<f64>, x: &mut Array2<f64>, coefs: &Array1<f64>) -> Array1<f64> {
+        let (errors, predicted) = self.filter_errors(known, 0, y, x, coefs);
+        Array::from_iter(errors.iter().zip(predicted).map(|(&e, p)| if p {e} else {f64::NAN})).slice(s![known.len()..]).to_owned()
+    }
+
+    /// forecasts of the last h rows of the prepared data with fixed coefficients, continuing the one-step-ahead
+    /// errors of the rows before, which are filtered unless known
+    pub(super) fn predict_coefs(&self, h: usize, y: &mut Array1<f64>, x: &mut Array2<f64>, coefs: &Array1<f64>, errors: Option<&Array1<f64>>) -> Array1<f64> {
+        let errors = match errors {
+            Some(errors) => errors.to_owned(),
+            None => self.filter_errors(&Array::zeros(0), h, y, x, coefs).0
+        };
+        let new_errors = self.forecast_errors(h, &errors, &x.slice(s![.., self.lag_end_col()..]).to_owned());
+
+        if self.uses_css() {
+            self.predict_multiplicative(h, y, x, coefs, &new_errors)
+        } else {
+            self.predict_internal(h, y, x, coefs, &new_errors)
+        }
+    }
+
+    /// one-step-ahead errors with fixed coefficients for all but the last h rows and whether each row was predicted,
+    /// continuing the known errors of the first rows, which are not predicted again.
+    /// Errors are zero for the rows before all lags are available, with missing values or where y is missing.
+    /// Missing values of y are replaced by their prediction and the lag and MA columns of x are filled as they go.
+    pub(super) fn filter_errors(&self, known: &Array1<f64>, h: usize, y: &mut Array1<f64>, x: &mut Array2<f64>, coefs: &Array1<f64>) -> (Array1<f64>, Vec<bool>) {
+        if self.uses_css() {
+            return self.css_errors(known, h, y, x, coefs);
+        }
+        let mut errors: Array1<f64> = Array::zeros(y.len());
+        errors.slice_mut(s![..known.len()]).assign(known);
+        let mut predicted = vec![false; y.len()];
+        for i in known.len()..y.len() - h {
+            self.move_up_lags(i, x, y);
+            self.move_up_errors(i, x, &errors);
+
+            let y_pred_i = x.slice(s![i, ..]).dot(coefs);
+            predicted[i] = !y[i].is_nan() && !y_pred_i.is_nan();
+            errors[i] = fill_missing(&mut y[i], y_pred_i);
+        }
+        (errors, predicted)
+    }
+
+    fn fit_internal(&self, y: &mut Array1<f64>, x: &mut Array2<f64>) -> Array1<f64> {
+        match self.method {
+            Method::Recursive => self.fit_recursive(y, x),
+            Method::HannanRissanen => self.fit_hannan_rissanen(y, x)
+        }
     }
 
-    fn fit_internal(&self, h: usize, y: &Array1<f64>, mut x: &mut Array2<f64>) -> (Array1<f64>, Array1<f64>) {
-        let (error_start_col, seasonal_error_start_col, seasonal_error_end_col) = self.error_cols();
+    /// missing values of y are replaced by their one-step-ahead prediction so that later lags are available,
+    /// but only rows that were complete before filling are used for estimation
+    fn fit_recursive(&self, y: &mut Array1<f64>, x: &mut Array2<f64>) -> Array1<f64> {
         let mut coefs: Array1<f64> = Array::zeros(x.shape()[1]);
         let mut errors: Array1<f64> = Array::zeros(y.len());
 
-        let end = y.len() - h;
-        for i in 1..end {
+        let complete = complete_rows(x, y);
+        for i in 1..y.len() {
 
-            self.move_up(i, &mut x, &errors, error_start_col, seasonal_error_start_col, 1);
-            self.move_up(i, &mut x, &errors, seasonal_error_start_col, seasonal_error_end_col, self.seasonal_order.s);
+            self.move_up_lags(i, x, y);
+            self.move_up_errors(i, x, &errors);
 
-            coefs = normal_equation::solve(x.slice(s![..i, ..]), y.slice(s![..i]));
+            coefs = solve_complete(x.slice(s![..i, ..]), y.slice(s![..i]), &complete[..i]);
             let y_pred_i = x.slice(s![i, ..]).dot(&coefs);
-            errors[i] = y[i] - y_pred_i;
+            errors[i] = fill_missing(&mut y[i], y_pred_i);
         }
-        (coefs, errors)
+        coefs
     }
 
-    fn predict_internal(&self, h: usize, y: &mut Array1<f64>, mut x: &mut Array2<f64>, coefs: &Array1<f64>, errors: &Array1<f64>) -> Array1<f64> {
+    /// 1. errors are estimated as the residuals of a long autoregression (with trend and exogenous variables)
+    /// 2. lagged errors fill the MA columns and all coefficients are estimated with one least squares regression
+    fn fit_hannan_rissanen(&self, y: &Array1<f64>, x: &mut Array2<f64>) -> Array1<f64> {
+        let lag_end_col = self.lag_end_col();
+        let n_trend = self.trend.n_cols();
+        let n_exog = x.shape()[1] - lag_end_col;
+
+        let end = y.len();
+        let m = self.long_ar_order(end);
+
+        let mut x_long: Array2<f64> = Array::zeros((y.len(), n_trend + m + n_exog));
+        x_long.slice_mut(s![.., ..n_trend]).assign(&x.slice(s![.., ..n_trend]));
+        x_long.slice_mut(s![.., n_trend + m..]).assign(&x.slice(s![.., lag_end_col..]));
+        for i in 0..end {
+            self.move_up(i, &mut x_long, y, n_trend, n_trend + m, 1);
+        }
+        let complete = complete_rows(&x_long, y);
+        let long_coefs = solve_complete(x_long.slice(s![m..end, ..]), y.slice(s![m..end]), &complete[m..end]);
 
-        let (lag_start_col, seasonal_lag_start_col, seasonal_lag_end_col) = self.lag_cols();
-        let (error_start_col, seasonal_error_start_col, seasonal_error_end_col) = self.error_cols();
+        let mut errors: Array1<f64> = Array::zeros(y.len());
+        for i in m..end {
+            let error = y[i] - x_long.slice(s![i, ..]).dot(&long_coefs);
+            errors[i] = if error.is_nan() {0.} else {error};
+        }
+
+        for i in 0..end {
+            self.move_up_errors(i, x, &errors);
+        }
+        let complete = complete_rows(x, y);
+        solve_complete(x.slice(s![m..end, ..]), y.slice(s![m..end]), &complete[m..end])
+    }
+
+    /// order of the long autoregression used in the first stage of Hannan-Rissanen,
+    /// long enough to cover the seasonal lags but leaving most of the n observations for estimation
+    fn long_ar_order(&self, n: usize) -> usize {
+        let max_lag = self.seasonal_orders
+            .iter()
+            .flat_map(|so| [so.p * so.s, so.q * so.s])
+            .chain([self.order.p, self.order.q])
+            .max()
+            .unwrap();
+        let m = ((12. * (n as f64 / 100.).powf(0.25)).floor() as usize).max(2 * max_lag);
+        m.min(n / 3).max(1)
+    }
+
+    fn predict_internal(&self, h: usize, y: &mut Array1<f64>, x: &mut Array2<f64>, coefs: &Array1<f64>, errors: &Array1<f64>) -> Array1<f64> {
 
         let start = y.len() - h;
         for i in start..y.len() {
 
-            self.move_up(i, &mut x, &y, lag_start_col, seasonal_lag_start_col, 1);
-            self.move_up(i, &mut x, &y, seasonal_lag_start_col, seasonal_lag_end_col, self.seasonal_order.s);
-
-            self.move_up(i, &mut x, &errors, error_start_col, seasonal_error_start_col, 1);
-            self.move_up(i, &mut x, &errors, seasonal_error_start_col, seasonal_error_end_col, self.seasonal_order.s);
+            self.move_up_lags(i, x, y);
+            self.move_up_errors(i, x, errors);
 
             y[i] = x.slice(s![i, ..]).dot(coefs);
         }
-        y.slice(s![-(h as isize)..]).to_owned()
+        y.slice(s![start..]).to_owned()
     }
 
+    /// past errors followed by h future MA innovations, see [FutureErrors]
     fn forecast_errors(&self, h: usize, errors: &Array1<f64>, exog: &Array2<f64>) -> Array1<f64> {
 
-        let errors_fit = errors.slice(s![..-(h as isize)]).to_owned();
+        let end = errors.len() - h;
+        let errors_fit = errors.slice(s![..end]).to_owned();
 
         let size = exog.shape()[0] - errors.len();  // exog may be longer than errors due to lags
         let exog = exog.slice(s![size.., ..]).to_owned();
 
-        let errors_forecast: Array1<f64> = if self.order.q + self.seasonal_order.q > 0 {
+        let n_ma = self.order.q + self.seasonal_orders.iter().map(|so| so.q).sum::<usize>();
+        let errors_forecast: Array1<f64> = if n_ma > 0 && self.future_errors == FutureErrors::Autoregressive {
 
-            let exog_future = exog.slice(s![-(h as isize).., ..]).to_owned();
-            let exog_fit = exog.slice(s![..-(h as isize), ..]).to_owned();
+            let exog_future = exog.slice(s![end.., ..]).to_owned();
+            let exog_fit = exog.slice(s![..end, ..]).to_owned();
         
-            let mut m = Model::sarima((self.order.p, 0, 0), (self.seasonal_order.p, 0, 0, self.seasonal_order.s));
+            let mut m = ModelBuilder::new()
+                .order((self.order.p, 0, 0))
+                .seasonal_orders(self.seasonal_orders.iter().map(|so| (so.p, 0, 0, so.s)).collect())
+                .seasonality(self.seasonality)
+                .missing(self.missing)
+                .build();
             m.forecast(&errors_fit, h, Some(&exog_fit), Some(&exog_future))
 
         } else {
@@ -79,7 +186,48 @@ impl Model {
     }
 }
 
+/// rows of x and y without missing values
+pub(super) fn complete_rows(x: &Array2<f64>, y: &Array1<f64>) -> Vec<bool> {
+    x.outer_iter()
+        .zip(y.iter())
+        .map(|(row, y_i)| !y_i.is_nan() && row.iter().all(|v| !v.is_nan()))
+        .collect()
+}
+
+/// least squares on the complete rows only
+pub(super) fn solve_complete(x: ArrayView2<f64>, y: ArrayView1<f64>, complete: &[bool]) -> Array1<f64> {
+    if complete.iter().all(|&c| c) {
+        return normal_equation::solve(x, y);
+    }
+    let rows: Vec<usize> = (0..complete.len()).filter(|&i| complete[i]).collect();
+    normal_equation::solve(x.select(Axis(0), &rows).view(), y.select(Axis(0), &rows).view())
+}
+
+/// returns the error of the prediction for y_i, replacing y_i with the prediction if it is missing
+fn fill_missing(y_i: &mut f64, y_pred_i: f64) -> f64 {
+    if y_i.is_nan() {
+        *y_i = y_pred_i;
+        0.
+    } else if y_pred_i.is_nan() {
+        0.
+    } else {
+        *y_i - y_pred_i
+    }
+}
+
 impl Model {
+    fn move_up_lags(&self, index: usize, x: &mut Array2<f64>, y: &Array1<f64>) {
+        for (start_col, end_col, s) in self.lag_cols() {
+            self.move_up(index, x, y, start_col, end_col, s);
+        }
+    }
+
+    fn move_up_errors(&self, index: usize, x: &mut Array2<f64>, errors: &Array1<f64>) {
+        for (start_col, end_col, s) in self.error_cols() {
+            self.move_up(index, x, errors, start_col, end_col, s);
+        }
+    }
+
     fn move_up(&self, index: usize, x: &mut Array2<f64>, values: &Array1<f64>, start_col: usize, end_col: usize, s: usize) {
         let s = s as isize;
         for (q, col) in (start_col..end_col).enumerate() {
@@ -90,21 +238,38 @@ impl Model {
         }
     }
     
-    fn error_cols(&self) -> (usize, usize, usize) {
-        let error_start_col: usize = 1;  // after intercept
-        let seasonal_error_start_col = error_start_col + self.order.q;
-        let seasonal_error_end_col = seasonal_error_start_col + self.seasonal_order.q;
-        (error_start_col, seasonal_error_start_col, seasonal_error_end_col)
+    /// (start column, end column, periodicity) of the lagged errors,
+    /// the non-seasonal terms followed by the terms of each seasonal period
+    pub(super) fn error_cols(&self) -> Vec<(usize, usize, usize)> {
+        let start_col = self.trend.n_cols();  // after trend
+        let orders = [(self.order.q, 1)].into_iter().chain(self.seasonal_orders.iter().map(|so| (so.q, so.s)));
+        blocks(start_col, orders)
+    }
+
+    /// (start column, end column, periodicity) of the lags of y,
+    /// the non-seasonal terms followed by the terms of each seasonal period
+    pub(super) fn lag_cols(&self) -> Vec<(usize, usize, usize)> {
+        let start_col = self.error_cols().last().unwrap().1;  // after errors
+        let orders = [(self.order.p, 1)].into_iter().chain(self.seasonal_orders.iter().map(|so| (so.p, so.s)));
+        blocks(start_col, orders)
     }
 
-    fn lag_cols(&self) -> (usize, usize, usize) {
-        let (_, _, lag_start_col) = self.error_cols();  // after errors
-        let seasonal_lag_start_col = lag_start_col + self.order.p;
-        let seasonal_lag_end_col = seasonal_lag_start_col + self.seasonal_order.p;
-        (lag_start_col, seasonal_lag_start_col, seasonal_lag_end_col)
+    /// first column after the lags of y, where exogenous variables start
+    pub(super) fn lag_end_col(&self) -> usize {
+        self.lag_cols().last().unwrap().1
     }
 }
 
+/// consecutive column ranges for (number of terms, periodicity) starting at start_col
+fn blocks(start_col: usize, orders: impl Iterator<Item = (usize, usize)>) -> Vec<(usize, usize, usize)> {
+    let mut start_col = start_col;
+    orders.map(|(n, s)| {
+        let block = (start_col, start_col + n, s);
+        start_col += n;
+        block
+    }).collect()
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,117 @@
+use numpy::ndarray::{Array, Array1, Array2, Axis, arr1, concatenate, s};
+use crate::model::{Model, Seasonality, optimise, polynomial};
+use super::{complete_rows, fill_missing, solve_complete};
+
+
+impl Model {
+    /// whether the AR or MA polynomials are products of more than one non-trivial factor,
+    /// otherwise the multiplicative model is the same as the additive one
+    pub(in crate::model) fn is_multiplicative(&self) -> bool {
+        let n_factors = |cols: Vec<(usize, usize, usize)>| cols.iter().filter(|(start, end, _)| end > start).count();
+        self.seasonality == Seasonality::Multiplicative && (n_factors(self.lag_cols()) > 1 || n_factors(self.error_cols()) > 1)
+    }
+
+    /// whether coefficients are estimated by conditional sum of squares, for multiplicative or constrained models
+    pub(in crate::model) fn uses_css(&self) -> bool {
+        self.is_multiplicative() || self.is_constrained()
+    }
+
+    /// conditional sum of squares estimation of the multiplicative or constrained model, starting from the
+    /// coefficients of the additive unconstrained one. AR and MA coefficients are found with Nelder-Mead, over
+    /// unconstrained parameters when stationarity or invertibility is enforced, trend and exogenous coefficients
+    /// by least squares given them.
+    ///
+    pub(super) fn fit_css(&self, y: &Array1<f64>, x: &Array2<f64>, coefs: &Array1<f64>) -> Array1<f64> {
+        let (start_col, end_col) = (self.trend.n_cols(), self.lag_end_col());
+        let with_arma = |params: &Array1<f64>| {
+            let mut coefs = coefs.to_owned();
+            coefs.slice_mut(s![start_col..end_col]).assign(&self.constrain_arma(params));
+            coefs
+        };
+
+        let params = self.unconstrain_arma(&coefs.slice(s![start_col..end_col]).to_owned());
+        let params = optimise::nelder_mead(|params| self.css(y, x, &with_arma(params)).1, &params, 0.1, 500 * params.len(), 1e-12);
+        self.css(y, x, &with_arma(&params)).0
+    }
+
+    /// returns the coefficients with the trend and exogenous ones estimated given the AR and MA ones
+    /// and the sum of squares of the one-step-ahead errors
+    fn css(&self, y: &Array1<f64>, x: &Array2<f64>, coefs: &Array1<f64>) -> (Array1<f64>, f64) {
+        let (ar, ma) = (self.ar_polynomial(coefs), self.ma_polynomial(coefs));
+        let end = y.len();
+        let start = ar.len() - 1;
+        if start >= end {
+            panic!("y used for fitting is not long enough based on model specification.")
+        }
+
+        // φ(B) y_t = z_t γ + θ(B) e_t, with z the trend and exogenous columns
+        let linear_cols = self.linear_cols(x.shape()[1]);
+        let u = polynomial::filter(&y.slice(s![..end]).to_owned(), &ar, &arr1(&[1.])).slice_move(s![start..]);
+        let z = x.slice(s![start..end, ..]).select(Axis(1), &linear_cols);
+        let complete = complete_rows(&z, &u);
+
+        // θ(B)^-1 applied to both sides, rows with missing values get a zero error
+        let values = concatenate![Axis(1), u.insert_axis(Axis(1)), z];
+        let mut filtered: Array2<f64> = Array::zeros(values.raw_dim());
+        for t in (0..values.shape()[0]).filter(|&t| complete[t]) {
+            let mut row = values.row(t).to_owned();
+            for j in 1..ma.len().min(t + 1) {
+                row = row - &filtered.row(t - j) * ma[j];
+            }
+            filtered.row_mut(t).assign(&row);
+        }
+
+        let (u, z) = (filtered.column(0), filtered.slice(s![.., 1..]));
+        let gamma = if linear_cols.is_empty() {Array::zeros(0)} else {solve_complete(z, u, &complete)};
+        let errors_fit = &u - &z.dot(&gamma);
+
+        let mut coefs = coefs.to_owned();
+        for (c, g) in linear_cols.iter().zip(gamma.iter()) {
+            coefs[*c] = *g;
+        }
+        let sse = errors_fit.mapv(|e| e * e).sum();
+        (coefs, sse)
+    }
+
+    /// one-step-ahead errors of the (product) polynomials with fixed coefficients for all but the last h rows,
+    /// continuing the known errors of the first rows, zero before all lags are available and where y or x are missing,
+    /// replacing missing values of y with their prediction, and whether each row was predicted
+    pub(super) fn css_errors(&self, known: &Array1<f64>, h: usize, y: &mut Array1<f64>, x: &Array2<f64>, coefs: &Array1<f64>) -> (Array1<f64>, Vec<bool>) {
+        let (ar, ma) = (self.ar_polynomial(coefs), self.ma_polynomial(coefs));
+        let linear_cols = self.linear_cols(x.shape()[1]);
+
+        let mut errors: Array1<f64> = Array::zeros(y.len());
+        errors.slice_mut(s![..known.len()]).assign(known);
+        let mut predicted = vec![false; y.len()];
+        for t in (ar.len() - 1).max(known.len())..y.len() - h {
+            let ar_part: f64 = (1..ar.len()).map(|k| -ar[k] * y[t - k]).sum();
+            let ma_part: f64 = (1..ma.len().min(t + 1)).map(|j| ma[j] * errors[t - j]).sum();
+            let linear_part: f64 = linear_cols.iter().map(|&c| x[[t, c]] * coefs[c]).sum();
+            let y_pred_t = ar_part + ma_part + linear_part;
+            predicted[t] = !y[t].is_nan() && !y_pred_t.is_nan();
+            errors[t] = fill_missing(&mut y[t], y_pred_t);
+        }
+        (errors, predicted)
+    }
+
+    /// trend and exogenous columns of the design matrix
+    fn linear_cols(&self, n_cols: usize) -> Vec<usize> {
+        (0..self.trend.n_cols()).chain(self.lag_end_col()..n_cols).collect()
+    }
+
+    /// forecasts of the differenced series with the (product) polynomials
+    /// φ(B) y_t = z_t γ + θ(B) e_t
+    pub(super) fn predict_multiplicative(&self, h: usize, y: &mut Array1<f64>, x: &Array2<f64>, coefs: &Array1<f64>, errors: &Array1<f64>) -> Array1<f64> {
+        let (ar, ma) = (self.ar_polynomial(coefs), self.ma_polynomial(coefs));
+        let linear_cols = self.linear_cols(x.shape()[1]);
+
+        let start = y.len() - h;
+        for i in start..y.len() {
+            let ar_part: f64 = (1..ar.len().min(i + 1)).map(|k| -ar[k] * y[i - k]).sum();
+            let ma_part: f64 = (1..ma.len().min(i + 1)).map(|j| ma[j] * errors[i - j]).sum();
+            let linear_part: f64 = linear_cols.iter().map(|&c| x[[i, c]] * coefs[c]).sum();
+            y[i] = ar_part + ma_part + linear_part;
+        }
+        y.slice(s![start..]).to_owned()
+    }
+}
@@ -10,7 +10,9 @@ pub(crate) fn solve(x: ArrayView2<f64>, y: ArrayView1<f64>) -> Array1<f64> {
     let square_inverse = match square.inv() {
         Err(_) => {
             let mut penalty = Array::eye(square.shape()[0]) * 1.;
-            penalty[[0, 0]] = 0.;  // intercept
+            if square[[0, 0]] > 0. {
+                penalty[[0, 0]] = 0.;  // intercept, unless the first column is all zeros without a trend
+            }
             (square + penalty).inv().expect("Should invert.")
         },
         Ok(x) => x,
@@ -45,4 +47,14 @@ mod tests {
 
         println!("{:?}", x);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn normal_equation_zero_first_column() {
+        // without a trend the first column can be an MA column of errors that are all still zero
+        let x: Array2<f64> = arr2(&[[0., 1.], [0., 2.], [0., 3.]]);
+        let y = arr1(&[2., 4., 6.]);
+        let coefs = solve(x.view(), y.view());
+        assert_eq!(coefs[0], 0.);
+        assert!((coefs[1] - 28. / 15.).abs() < 1e-12);
+    }
+}
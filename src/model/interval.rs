@@ -0,0 +1,103 @@
+use numpy::ndarray::{Array1, Array2, Axis};
+use super::Model;
+use super::polynomial;
+use crate::random::{Rng, sample_paths};
+
+
+/// inverse of the standard normal cumulative distribution function,
+/// [Acklam's algorithm](https://web.archive.org/web/20151030215612/http://home.online.no/~pjacklam/notes/invnorm/)
+/// with a relative error below 1.15e-9
+#[allow(clippy::excessive_precision)]
+pub(crate) fn normal_quantile(p: f64) -> f64 {
+    if !(0. ..=1.).contains(&p) {
+        panic!("Probability must be between 0 and 1, got {}.", p);
+    }
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02, 1.383577518672690e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02, 6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00, -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00, 3.754408661907416e+00];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+
+    if p < P_LOW {
+        tail((-2. * p.ln()).sqrt())
+    } else if p > 1. - P_LOW {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    }
+}
+
+impl Model {
+    /// variance of the 1..=h step ahead forecast errors on the transformed scale, σ² Σ ψ_j²,
+    /// where ψ are the weights of the MA(∞) representation of the model including differencing
+    pub(super) fn forecast_variance(&self, h: usize) -> Array1<f64> {
+        let coefs = self.coefs.as_ref().expect("Model must be fit before forecast variance");
+        let sigma2 = self.sigma2.expect("Model must be fit before forecast variance");
+
+        let ar = self.integrated_ar_polynomial(coefs);
+        let ma = self.ma_polynomial(coefs);
+        let psi = polynomial::psi_weights(&ar, &ma, h);
+
+        let mut variance = psi.mapv(|p| p * p);
+        variance.accumulate_axis_inplace(Axis(0), |&prev, cur| *cur += prev);
+        variance * sigma2
+    }
+
+    /// - h: horizons to forecast
+    /// - x: future exongenous variables, same length as h
+    /// - n: number of draws
+    ///
+    /// returns n simulated future paths, one row per path, from normal innovations driving the MA(∞)
+    /// representation of the model on the transformed scale, transformed back like the bounds of `predict_interval`
+    pub fn sample(&self, h: usize, x: Option<&Array2<f64>>, n: usize, rng: &mut Rng) -> Array2<f64> {
+        let coefs = self.coefs.as_ref().expect("Model must be fit before sample");
+        let psi = polynomial::psi_weights(&self.integrated_ar_polynomial(coefs), &self.ma_polynomial(coefs), h);
+        let mut paths = sample_paths(&self.predict_transformed(h, x), &psi, self.sigma2.unwrap(), n, rng);
+        for mut row in paths.rows_mut() {
+            let z = row.to_owned();
+            row.assign(&self.inverse_transform(&z, None));
+        }
+        paths
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Noise;
+    use numpy::ndarray::arr1;
+
+    #[test]
+    fn interval_sample() {
+        let mut model = Model::arima(0, 1, 0);
+        let y = model.simulate(100, &arr1(&[0.]), 1., None, Noise::Normal, 3, 0);
+        model.fit(&y, None);
+
+        // a random walk's forecast variance grows linearly
+        let draws = model.sample(4, None, 20_000, &mut Rng::new(4));
+        let variance = draws.var_axis(Axis(0), 0.);
+        let expected = model.forecast_variance(4);
+        assert!((0..4).all(|j| (variance[j] / expected[j] - 1.).abs() < 0.05));
+        assert!((draws.mean_axis(Axis(0)).unwrap()[3] - model.predict(4, None)[3]).abs() < 0.05);
+        // and each path is a random walk, its steps the innovations
+        let steps = draws.column(3).to_owned() - draws.column(2);
+        assert!((steps.var(0.) / model.sigma2.unwrap() - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    fn interval_normal_quantile() {
+        let round = |x: f64| (1e4 * x).round() / 1e4;
+        assert_eq!(round(normal_quantile(0.5)), 0.);
+        assert_eq!(round(normal_quantile(0.975)), 1.96);
+        assert_eq!(round(normal_quantile(0.025)), -1.96);
+        assert_eq!(round(normal_quantile(0.995)), 2.5758);
+        assert_eq!(round(normal_quantile(0.001)), -3.0902);
+    }
+}
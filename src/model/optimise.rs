@@ -0,0 +1,87 @@
+use numpy::ndarray::Array1;
+
+
+/// minimum of f with the [Nelder-Mead](https://en.wikipedia.org/wiki/Nelder%E2%80%93Mead_method) simplex method
+/// - f: objective, non-finite values are treated as infinitely bad
+/// - x0: starting point
+/// - step: size of the initial simplex along each axis
+/// - max_iter: maximum number of iterations
+/// - tol: stop when the objective values of the simplex are within tol of each other
+pub(super) fn nelder_mead(f: impl Fn(&Array1<f64>) -> f64, x0: &Array1<f64>, step: f64, max_iter: usize, tol: f64) -> Array1<f64> {
+    let n = x0.len();
+    if n == 0 {
+        return x0.to_owned();
+    }
+    let eval = |x: &Array1<f64>| {
+        let value = f(x);
+        if value.is_finite() {value} else {f64::INFINITY}
+    };
+
+    let mut simplex: Vec<(Array1<f64>, f64)> = (0..=n)
+        .map(|i| {
+            let mut x = x0.to_owned();
+            if i > 0 {
+                x[i - 1] += step;
+            }
+            let value = eval(&x);
+            (x, value)
+        })
+        .collect();
+
+    for _ in 0..max_iter {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[n].1);
+        if (worst - best).abs() <= tol * (1. + best.abs()) {
+            break;
+        }
+
+        let centroid = simplex[..n].iter().fold(Array1::zeros(n), |acc, (x, _)| acc + x) / n as f64;
+        let worst_x = simplex[n].0.clone();
+        let towards = |t: f64| &centroid + &((&worst_x - &centroid) * t);
+
+        let reflected = towards(-1.);
+        let reflected_value = eval(&reflected);
+        if reflected_value < simplex[0].1 {
+            let expanded = towards(-2.);
+            let expanded_value = eval(&expanded);
+            simplex[n] = if expanded_value < reflected_value {(expanded, expanded_value)} else {(reflected, reflected_value)};
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted = if reflected_value < simplex[n].1 {towards(-0.5)} else {towards(0.5)};
+            let contracted_value = eval(&contracted);
+            if contracted_value < reflected_value.min(simplex[n].1) {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                let best = simplex[0].0.clone();
+                for (x, value) in simplex.iter_mut().skip(1) {
+                    *x = &best + &((&*x - &best) * 0.5);
+                    *value = eval(x);
+                }
+            }
+        }
+    }
+    simplex.into_iter().min_by(|a, b| a.1.total_cmp(&b.1)).unwrap().0
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::arr1;
+
+    #[test]
+    fn optimise_nelder_mead_rosenbrock() {
+        let rosenbrock = |x: &Array1<f64>| (1. - x[0]).powi(2) + 100. * (x[1] - x[0] * x[0]).powi(2);
+        let x = nelder_mead(rosenbrock, &arr1(&[-1.2, 1.]), 0.5, 5000, 1e-14);
+        assert_eq!(x.mapv(|v| (1e3 * v).round() / 1e3), arr1(&[1., 1.]));
+    }
+
+    #[test]
+    fn optimise_nelder_mead_infinite() {
+        // the region x < 0 is infeasible
+        let f = |x: &Array1<f64>| if x[0] < 0. {f64::NAN} else {(x[0] - 0.5).powi(2)};
+        let x = nelder_mead(f, &arr1(&[2.]), 1., 500, 1e-12);
+        assert_eq!((1e3 * x[0]).round() / 1e3, 0.5);
+    }
+}
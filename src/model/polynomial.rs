@@ -0,0 +1,172 @@
+use numpy::ndarray::{Array, Array1, s};
+use super::{Model, Seasonality};
+
+
+/// coefficients by lag of the product of two lag polynomials
+pub(super) fn multiply(a: &Array1<f64>, b: &Array1<f64>) -> Array1<f64> {
+    let mut c: Array1<f64> = Array::zeros(a.len() + b.len() - 1);
+    for (i, a_i) in a.iter().enumerate() {
+        for (j, b_j) in b.iter().enumerate() {
+            c[i + j] += a_i * b_j;
+        }
+    }
+    c
+}
+
+/// coefficients by lag of the differencing polynomial (1 - B)^d (1 - B^s)^D ...
+/// diffs: (degree of differences, periodicity), e.g. [(d, 1), (D, s)]
+pub(super) fn diff_polynomial(diffs: &[(usize, usize)]) -> Array1<f64> {
+    let mut poly = Array::ones(1);
+    for &(degree, lag) in diffs {
+        let mut factor: Array1<f64> = Array::zeros(lag + 1);
+        factor[0] = 1.;
+        factor[lag] = -1.;
+        for _ in 0..degree {
+            poly = multiply(&poly, &factor);
+        }
+    }
+    poly
+}
+
+/// first n weights of the MA(∞) representation y_t = ψ(B) e_t of ar(B) y_t = ma(B) e_t
+/// ar: coefficients by lag of the AR polynomial, starting with 1
+/// ma: coefficients by lag of the MA polynomial, starting with 1
+pub(super) fn psi_weights(ar: &Array1<f64>, ma: &Array1<f64>, n: usize) -> Array1<f64> {
+    let mut psi: Array1<f64> = Array::zeros(n);
+    for j in 0..n {
+        let theta_j = if j < ma.len() {ma[j]} else {0.};
+        psi[j] = theta_j - (1..=j.min(ar.len() - 1)).map(|i| ar[i] * psi[j - i]).sum::<f64>();
+    }
+    psi
+}
+
+/// f solving ma(B) f_t = ar(B) z_t with zero pre-sample values,
+/// e.g. the one-step-ahead errors of z when ar and ma are the polynomials of its ARMA model
+/// ar: coefficients by lag of the AR polynomial, starting with 1
+/// ma: coefficients by lag of the MA polynomial, starting with 1
+pub(super) fn filter(z: &Array1<f64>, ar: &Array1<f64>, ma: &Array1<f64>) -> Array1<f64> {
+    let mut f: Array1<f64> = Array::zeros(z.len());
+    for t in 0..z.len() {
+        let ar_part: f64 = (0..=t.min(ar.len() - 1)).map(|i| ar[i] * z[t - i]).sum();
+        let ma_part: f64 = (1..=t.min(ma.len() - 1)).map(|j| ma[j] * f[t - j]).sum();
+        f[t] = ar_part - ma_part;
+    }
+    f
+}
+
+impl Model {
+    /// AR polynomial (1 - φ_1 B - ...)(1 - Φ_1 B^s - ...) of the differenced series, or 1 - φ_1 B - ... - Φ_1 B^s - ...
+    /// with additive seasonality
+    pub(super) fn ar_polynomial(&self, coefs: &Array1<f64>) -> Array1<f64> {
+        let terms = self.lag_cols()
+            .into_iter()
+            .map(|(start_col, end_col, s)| (coefs.slice(s![start_col..end_col]).mapv(|c| -c), s))
+            .collect();
+        lag_polynomial(terms, self.seasonality)
+    }
+
+    /// MA polynomial (1 + θ_1 B + ...)(1 + Θ_1 B^s + ...) of the differenced series, or 1 + θ_1 B + ... + Θ_1 B^s + ...
+    /// with additive seasonality
+    pub(super) fn ma_polynomial(&self, coefs: &Array1<f64>) -> Array1<f64> {
+        let terms = self.error_cols()
+            .into_iter()
+            .map(|(start_col, end_col, s)| (coefs.slice(s![start_col..end_col]).to_owned(), s))
+            .collect();
+        lag_polynomial(terms, self.seasonality)
+    }
+
+    /// AR polynomial of the series before differencing, including the differencing polynomial
+    pub(super) fn integrated_ar_polynomial(&self, coefs: &Array1<f64>) -> Array1<f64> {
+        let diff = diff_polynomial(&self.differences());
+        multiply(&self.ar_polynomial(coefs), &diff)
+    }
+}
+
+/// product, or sum with additive seasonality, of 1 + terms at lags s, 2s, ... for each (terms, s)
+fn lag_polynomial(terms: Vec<(Array1<f64>, usize)>, seasonality: Seasonality) -> Array1<f64> {
+    let factors = terms.iter().map(|(terms, s)| {
+        let mut factor: Array1<f64> = Array::zeros(1 + terms.len() * s);
+        factor[0] = 1.;
+        for (i, term) in terms.iter().enumerate() {
+            factor[(i + 1) * s] += term;
+        }
+        factor
+    });
+    match seasonality {
+        Seasonality::Multiplicative => factors.fold(Array::ones(1), |poly, factor| multiply(&poly, &factor)),
+        Seasonality::Additive => factors.fold(Array::ones(1), |poly, factor| {
+            let mut sum: Array1<f64> = Array::zeros(poly.len().max(factor.len()));
+            sum.slice_mut(s![..poly.len()]).assign(&poly);
+            sum.slice_mut(s![1..factor.len()]).scaled_add(1., &factor.slice(s![1..]));
+            sum
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelBuilder;
+    use numpy::ndarray::arr1;
+
+    #[test]
+    fn polynomial_multiply() {
+        let a = arr1(&[1., -0.5]);
+        let b = arr1(&[1., 0., 0., -0.25]);
+        assert_eq!(multiply(&a, &b), arr1(&[1., -0.5, 0., -0.25, 0.125]));
+    }
+
+    #[test]
+    fn polynomial_diff_polynomial() {
+        assert_eq!(diff_polynomial(&[(0, 1), (0, 0)]), arr1(&[1.]));
+        assert_eq!(diff_polynomial(&[(2, 1)]), arr1(&[1., -2., 1.]));
+        assert_eq!(diff_polynomial(&[(1, 1), (1, 3)]), arr1(&[1., -1., 0., -1., 1.]));
+        assert_eq!(diff_polynomial(&[(0, 1), (1, 2), (1, 3)]), arr1(&[1., 0., -1., -1., 0., 1.]));
+    }
+
+    #[test]
+    fn polynomial_psi_weights() {
+        // AR(1): ψ_j = φ^j
+        let psi = psi_weights(&arr1(&[1., -0.5]), &arr1(&[1.]), 4);
+        assert_eq!(psi, arr1(&[1., 0.5, 0.25, 0.125]));
+
+        // random walk: ψ_j = 1
+        let psi = psi_weights(&diff_polynomial(&[(1, 1)]), &arr1(&[1.]), 3);
+        assert_eq!(psi, arr1(&[1., 1., 1.]));
+
+        // MA(1): ψ = 1, θ, 0, ...
+        let psi = psi_weights(&arr1(&[1.]), &arr1(&[1., 0.4]), 3);
+        assert_eq!(psi, arr1(&[1., 0.4, 0.]));
+    }
+
+    #[test]
+    fn polynomial_filter() {
+        // differencing
+        let z = arr1(&[1., 3., 6., 10.]);
+        assert_eq!(filter(&z, &diff_polynomial(&[(1, 1)]), &arr1(&[1.])), arr1(&[1., 2., 3., 4.]));
+
+        // inverts an MA(1): z = (1 + 0.5 B) e
+        let e = arr1(&[1., -2., 0.5, 3.]);
+        let z = arr1(&[1., -1.5, -0.5, 3.25]);
+        assert_eq!(filter(&z, &arr1(&[1.]), &arr1(&[1., 0.5])), e);
+    }
+
+    #[test]
+    fn polynomial_lag_polynomials_multiplicative() {
+        let model = Model::sarima((1, 0, 1), (1, 0, 1, 3));
+        // intercept, ma.L1, ma.S.L3, ar.L1, ar.S.L3
+        let coefs = arr1(&[10., 0.3, 0.2, 0.5, 0.4]);
+        assert_eq!(model.ar_polynomial(&coefs), arr1(&[1., -0.5, 0., -0.4, 0.2]));
+        assert_eq!(model.ma_polynomial(&coefs).mapv(|c| (1e9 * c).round() / 1e9), arr1(&[1., 0.3, 0., 0.2, 0.06]));
+    }
+
+    #[test]
+    fn polynomial_lag_polynomials() {
+        let model = ModelBuilder::new().order((2, 0, 1)).seasonal_order((1, 0, 1, 3)).seasonality(Seasonality::Additive).build();
+        // intercept, ma.L1, ma.S.L3, ar.L1, ar.L2, ar.S.L3
+        let coefs = arr1(&[10., 0.3, 0.2, 0.5, -0.1, 0.4]);
+        assert_eq!(model.ar_polynomial(&coefs), arr1(&[1., -0.5, 0.1, -0.4]));
+        assert_eq!(model.ma_polynomial(&coefs), arr1(&[1., 0.3, 0., 0.2]));
+    }
+}
@@ -2,26 +2,89 @@ mod difference;
 mod lags;
 
 use super::Model;
-use std::cmp::max;
 use numpy::ndarray::{Array, Array1, Array2, Axis, concatenate, s};
 
 
 impl Model {
+    /// predictions of the differenced series include the trend, so integrating them turns a constant
+    /// into a drift (d + D = 1) and a linear time trend into a quadratic one, as in the differenced equation.
     pub(super) fn integrate_predictions(&self, y_preds: &Array1<f64>, endog_fit: &Array1<f64>) -> Array1<f64> {
-        let intercept = self.coefs.as_ref().unwrap()[0];
-        let mut y_preds = y_preds - intercept;
-        y_preds = difference::integrate_all(&y_preds, endog_fit, self.order.d, self.seasonal_order.d, self.seasonal_order.s);
-        y_preds + intercept
+        difference::integrate_all(y_preds, endog_fit, &self.differences())
+    }
+
+    /// (degree of differences, periodicity), the non-seasonal differences followed by those of each seasonal period
+    pub(super) fn differences(&self) -> Vec<(usize, usize)> {
+        [(self.order.d, 1)].into_iter().chain(self.seasonal_orders.iter().map(|so| (so.d, so.s))).collect()
+    }
+
+    /// observations lost at the start by differencing, d + D * s summed over the seasonal periods
+    pub(super) fn nobs_differenced(&self) -> usize {
+        self.differences().iter().map(|(d, s)| d * s).sum()
     }
 }
 
 impl Model {
+    /// replaces missing values of y used for fitting with the values implied by the differenced series,
+    /// in which missing values have been filled with their one-step-ahead predictions
+    /// - endog_diff: differenced y used for fitting followed by h zeros
+    /// - y: endog_diff after lags are dropped and missing values filled
+    pub(super) fn fill_missing_levels(&self, endog_fit: &Array1<f64>, endog_diff: &Array1<f64>, y: &Array1<f64>, h: usize) -> Array1<f64> {
+        if !endog_fit.iter().any(|v| v.is_nan()) {
+            return endog_fit.to_owned();
+        }
+        let nobs_lost = endog_diff.len() - y.len();
+        let n = endog_diff.len() - h;
+        let mut diff_filled = endog_diff.slice(s![..n]).to_owned();
+        diff_filled.slice_mut(s![nobs_lost..]).assign(&y.slice(s![..n - nobs_lost]));
+        difference::fill_levels(endog_fit, &diff_filled, &self.differences())
+    }
+
     pub(super) fn unwrap_x(&self, x: Option<&Array2<f64>>, default_length: usize) -> Array2<f64> {
         let x = x.unwrap_or(&Array::zeros((default_length, 0))).to_owned();
         self.check_x_size(default_length, &x);
         x
     }
 
+    /// future exogenous variables, padded with NaN up to h rows when they only enter with lags
+    /// so that the last rows are never used
+    pub(super) fn unwrap_x_future(&self, x: Option<&Array2<f64>>, h: usize) -> Array2<f64> {
+        let n_exog = self.n_exog();
+        let required = if n_exog == 0 {h} else {h.saturating_sub(self.min_exog_lag())};
+
+        let x = match x {
+            Some(x) if !(x.shape()[1] == 0 && required == 0) => x.to_owned(),
+            _ if required == 0 => Array::zeros((0, n_exog)),
+            _ => Array::zeros((h, 0))
+        };
+        let rows = x.shape()[0];
+        if rows < required || rows > h {
+            if required == h {
+                panic!("x is length: {}. It should be length: {}.", rows, h);
+            }
+            panic!("x is length: {}. It should be between length: {} and {}.", rows, required, h);
+        }
+        self.check_x_size(rows, &x);
+        if x.iter().any(|v| v.is_nan()) {
+            panic!("x used for predict contains missing values (NaN). Future exogenous variables must be known.")
+        }
+
+        let padding: Array2<f64> = Array::from_elem((h - rows, n_exog), f64::NAN);
+        concatenate![Axis(0), x.view(), padding.view()]
+    }
+
+    /// lags of exogenous variable j
+    pub(super) fn exog_lags(&self, j: usize) -> Vec<usize> {
+        if self.exog_lags.is_empty() {vec![0]} else {self.exog_lags[j].clone()}
+    }
+
+    fn max_exog_lag(&self) -> usize {
+        self.exog_lags.iter().flatten().copied().max().unwrap_or(0)
+    }
+
+    fn min_exog_lag(&self) -> usize {
+        (0..self.n_exog()).flat_map(|j| self.exog_lags(j)).min().unwrap_or(0)
+    }
+
     pub(super) fn difference_xy(
         &self,
         exog_fit: &Array2<f64>,
@@ -31,46 +94,107 @@ impl Model {
     ) -> (Array2<f64>, Array1<f64>) {
 
         let exog = concatenate![Axis(0), exog_fit.view(), exog_future.view()];
-        let exog_diff = difference::diff_all2d(&exog, self.order.d, self.seasonal_order.d, self.seasonal_order.s);
+        let exog_diff = self.difference_exog(&exog);
 
-        let mut endog_diff = difference::diff_all1d(&endog_fit, self.order.d, self.seasonal_order.d, self.seasonal_order.s);
+        let mut endog_diff = difference::diff_all1d(endog_fit, &self.differences());
         endog_diff = concatenate![Axis(0), endog_diff.view(), Array::zeros(h).view()];
 
         (exog_diff, endog_diff)
     }
 
-    pub(super) fn prepare_xy(&self, exog: &Array2<f64>, endog: &Array1<f64>) -> (Array2<f64>, Array1<f64>) {
+    /// differences the columns of exog chosen by the Exog setting,
+    /// the other columns are kept in levels and aligned with the differenced ones
+    pub(super) fn difference_exog(&self, exog: &Array2<f64>) -> Array2<f64> {
+        let diffed = difference::diff_all2d(exog, &self.differences());
+        let nobs_lost = exog.shape()[0] - diffed.shape()[0];
 
-        let nobs_lost = max(self.order.p, self.seasonal_order.p * self.seasonal_order.s);
+        let mut exog_diff = exog.slice(s![nobs_lost.., ..]).to_owned();
+        for j in 0..exog.shape()[1] {
+            if self.exog.is_differenced(j) {
+                exog_diff.column_mut(j).assign(&diffed.column(j));
+            }
+        }
+        exog_diff
+    }
+
+    /// design matrix and y of the differenced series without the first rows, which only provide lags
+    /// - start: index in y used for fitting of the first observation before differencing, so that time continues
+    pub(super) fn prepare_xy(&self, exog: &Array2<f64>, endog: &Array1<f64>, start: usize) -> (Array2<f64>, Array1<f64>) {
+
+        let nobs_lost = self.nobs_lost();
         if nobs_lost >= endog.len() {
             panic!("y used for fitting is not long enough based on model specification.")
         }
         let nobs = endog.len() - nobs_lost;
 
-        let x = self.prepare_x(&exog, &endog, nobs);
+        let x = self.prepare_x(exog, endog, nobs, start);
         let y = endog.slice(s![-(nobs as isize)..]).to_owned();
         (x, y)
     }
 
-    fn prepare_x(&self, exog: &Array2<f64>, endog: &Array1<f64>, nobs: usize) -> Array2<f64> {
+    /// rows of the differenced series dropped because they only provide lags of y or x
+    fn nobs_lost(&self) -> usize {
+        self.seasonal_orders
+            .iter()
+            .map(|so| so.p * so.s)
+            .chain([self.order.p, self.max_exog_lag()])
+            .max()
+            .unwrap()
+    }
+
+    /// number of last observations from which forecasts can continue given their one-step-ahead errors:
+    /// those lost to differencing and lags and enough rows for the lags of the AR and MA polynomials
+    pub(super) fn state_len(&self, coefs: &Array1<f64>) -> usize {
+        let lags = self.ar_polynomial(coefs).len().max(self.ma_polynomial(coefs).len());
+        self.nobs_differenced() + self.nobs_lost() + lags
+    }
+
+    fn prepare_x(&self, exog: &Array2<f64>, endog: &Array1<f64>, nobs: usize, start: usize) -> Array2<f64> {
 
-        let y_lags = lags::create_lags(&endog, self.order.p, self.order.s);
-        let y_lags_seasonal = lags::create_lags(&endog, self.seasonal_order.p, self.seasonal_order.s);
+        let mut columns = vec![self.prepare_trend(endog.len(), nobs, start)];
+        for (start_col, end_col, _) in self.error_cols() {
+            columns.push(Array::zeros((nobs, end_col - start_col)));
+        }
+        for (start_col, end_col, s) in self.lag_cols() {
+            let y_lags = lags::create_lags(endog, end_col - start_col, s);
+            columns.push(y_lags.slice(s![-(nobs as isize).., ..]).to_owned());
+        }
+        columns.push(self.prepare_exog(exog, nobs));
 
-        let errors: Array2<f64> = Array::zeros((nobs, self.order.q));
-        let errors_seasonal: Array2<f64> = Array::zeros((nobs, self.seasonal_order.q));
-        let intercept: Array2<f64> = Array::ones((nobs, 1));
+        let views: Vec<_> = columns.iter().map(|c| c.view()).collect();
+        concatenate(Axis(1), &views).unwrap()
+    }
 
-        let nobs = nobs as isize;
-        concatenate![
-            Axis(1),
-            intercept.view(),
-            errors.view(),
-            errors_seasonal.view(),
-            y_lags.slice(s![-nobs.., ..]),
-            y_lags_seasonal.slice(s![-nobs.., ..]),
-            exog.slice(s![-nobs.., ..])
-        ]
+    /// each exogenous variable at each of its lags for the last nobs rows
+    fn prepare_exog(&self, exog: &Array2<f64>, nobs: usize) -> Array2<f64> {
+        let max_lag = self.max_exog_lag();
+        let mut columns = Vec::new();
+        for (j, col) in exog.columns().into_iter().enumerate() {
+            let col = col.to_owned();
+            let col_lags = lags::create_lags(&col, max_lag, 1);
+            for lag in self.exog_lags(j) {
+                let values = if lag == 0 {col.view()} else {col_lags.column(lag - 1)};
+                columns.push(values.slice(s![-(nobs as isize)..]).to_owned().insert_axis(Axis(1)));
+            }
+        }
+        let views: Vec<_> = columns.iter().map(|c| c.view()).collect();
+        concatenate(Axis(1), &views).unwrap_or_else(|_| Array::zeros((nobs, 0)))
+    }
+
+    /// constant and / or time trend columns for the last nobs rows of a differenced series of length len.
+    /// time is counted from the first observation of y before differencing, at index start of y used for fitting,
+    /// so that it continues into the future.
+    pub(super) fn prepare_trend(&self, len: usize, nobs: usize, start: usize) -> Array2<f64> {
+        let mut trend: Array2<f64> = Array::zeros((nobs, 0));
+        if self.trend.has_constant() {
+            trend = concatenate![Axis(1), trend.view(), Array::ones((nobs, 1)).view()];
+        }
+        if self.trend.has_time() {
+            let first = (start + len - nobs + self.nobs_differenced() + 1) as f64;
+            let time = Array::range(first, first + nobs as f64, 1.).insert_axis(Axis(1));
+            trend = concatenate![Axis(1), trend.view(), time.view()];
+        }
+        trend
     }
 
     fn check_x_size(&self, size: usize, x: &Array2<f64>) {
@@ -100,7 +224,7 @@ mod tests {
         let model = Model::sarima((2, 1, 3), (1, 1, 1, 7));
         let y = arr1(&[0., 1., 2., 3.]);
         let x: Array2<f64> = Array::zeros((y.len(), 0));
-        model.prepare_xy(&x, &y);
+        model.prepare_xy(&x, &y, 0);
     }
 
     #[test]
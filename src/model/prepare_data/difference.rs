@@ -1,4 +1,5 @@
 use numpy::ndarray::{Array1, Array2, Axis, s, concatenate};
+use crate::model::polynomial::diff_polynomial;
 
 /// a: time series to difference
 /// d: degree of differences
@@ -12,11 +13,9 @@ fn diff2d(a: &Array2<f64>, d: usize, s: usize) -> Array2<f64> {
 }
 
 /// a: time series to difference
-/// d: degree of differences
-/// s_d: degree od seasonal differences
-/// s: peridicity of season
-pub(super) fn diff_all2d(a: &Array2<f64>, d: usize, s_d: usize, s: usize) -> Array2<f64> {
-    diff2d(&diff2d(&a, d, 1), s_d, s)
+/// diffs: (degree of differences, periodicity) applied in order, e.g. [(d, 1), (D, s)]
+pub(super) fn diff_all2d(a: &Array2<f64>, diffs: &[(usize, usize)]) -> Array2<f64> {
+    diffs.iter().fold(a.to_owned(), |a, &(d, s)| diff2d(&a, d, s))
 }
 
 
@@ -35,11 +34,9 @@ fn diff1d(y: &Array1<f64>, d: usize, s: usize) -> Array1<f64> {
 // diff2d(&y, d, s).slice(s![.., 0]).to_owned()
 
 /// y: time series to difference
-/// d: degree of differences
-/// s_d: degree od seasonal differences
-/// s: peridicity of season
-pub(super) fn diff_all1d(y: &Array1<f64>, d: usize, s_d: usize, s: usize) -> Array1<f64> {
-    diff1d(&diff1d(&y, d, 1), s_d, s)
+/// diffs: (degree of differences, periodicity) applied in order, e.g. [(d, 1), (D, s)]
+pub(super) fn diff_all1d(y: &Array1<f64>, diffs: &[(usize, usize)]) -> Array1<f64> {
+    diffs.iter().fold(y.to_owned(), |y, &(d, s)| diff1d(&y, d, s))
 }
 
 /// differences back to the previous level therefore if d = n, this operation needs running n times.
@@ -56,29 +53,40 @@ fn integrate(y: &Array1<f64>, y_last: &Array1<f64>, s: usize) -> Array1<f64> {
 
 /// y_preds: predictions before being integrated
 /// y_original: the original values of y for used for fitting before being differenced
-/// d: degree of differences
-/// s_d: degree od seasonal differences
-/// s: peridicity of season
-pub(super) fn integrate_all(y_preds: &Array1<f64>, y_original: &Array1<f64>, d: usize, s_d: usize, s: usize) -> Array1<f64> {
+/// diffs: (degree of differences, periodicity) in the order they were applied
+pub(super) fn integrate_all(y_preds: &Array1<f64>, y_original: &Array1<f64>, diffs: &[(usize, usize)]) -> Array1<f64> {
 
     let mut y_integrated = y_preds.to_owned();
 
-    for i in (0..s_d).rev() {
-        let y_last = diff_all1d(&y_original, d, i, s);
-        y_integrated = integrate(&y_integrated, &y_last, s);
+    for (k, &(d, s)) in diffs.iter().enumerate().rev() {
+        for i in (0..d).rev() {
+            let y_last = diff1d(&diff_all1d(y_original, &diffs[..k]), i, s);
+            y_integrated = integrate(&y_integrated, &y_last, s);
+        }
     }
+    y_integrated
+}
 
-    for i in (0..d).rev() {
-        let y_last = diff1d(&y_original, i, 1);
-        y_integrated = integrate(&y_integrated, &y_last, 1);
+/// replaces missing values of y with the value implied by y_diff, its differenced series without missing values
+/// y: time series with missing values
+/// y_diff: differenced y with missing values filled
+/// diffs: (degree of differences, periodicity) applied in order, e.g. [(d, 1), (D, s)]
+pub(super) fn fill_levels(y: &Array1<f64>, y_diff: &Array1<f64>, diffs: &[(usize, usize)]) -> Array1<f64> {
+    let poly = diff_polynomial(diffs);
+    let k = poly.len() - 1;
+    let mut y = y.to_owned();
+    for t in k..y.len() {
+        if y[t].is_nan() {
+            y[t] = y_diff[t - k] - (1..=k).map(|j| poly[j] * y[t - j]).sum::<f64>();
+        }
     }
-    y_integrated
+    y
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use numpy::ndarray::{Array, arr1, s};
+    use numpy::ndarray::{Array, arr1};
     // run with "cargo test -- --show-output" to see output
 
     #[test]
@@ -115,7 +123,7 @@ mod tests {
 
         let y: Array1<f64> = arr1(&[7., 6., 4., 3., 4., 5., 6., 7., 6., 4., 3., 4., 5., 6., 7., 6., 4., 3., 4., 5., 6., 7., 6., 4., 3., 4., 5., 6.]);
 
-        let y_diff = diff_all1d(&y, d, s_d, s);
+        let y_diff = diff_all1d(&y, &[(d, 1), (s_d, s)]);
         let result: Array1<f64> = Array::zeros(y.len() - s);
         assert_eq!(result, y_diff);
     }
@@ -153,8 +161,8 @@ mod tests {
         let y_train = y.slice(s![..cutoff]).to_owned();
         let y_future = y.slice(s![cutoff..]).to_owned();
 
-        let mut y_preds = diff_all1d(&y, d, s_d, s).slice(s![-from_end..]).to_owned();
-        y_preds = integrate_all(&y_preds, &y_train, d, s_d, s);
+        let mut y_preds = diff_all1d(&y, &[(d, 1), (s_d, s)]).slice(s![-from_end..]).to_owned();
+        y_preds = integrate_all(&y_preds, &y_train, &[(d, 1), (s_d, s)]);
         
         assert_eq!(y_future, y_preds);
     }
@@ -171,8 +179,8 @@ mod tests {
         let y_train = y.slice(s![..cutoff]).to_owned();
         let y_future = y.slice(s![cutoff..]).to_owned();
 
-        let mut y_preds = diff_all1d(&y, d, s_d, s).slice(s![-from_end..]).to_owned();
-        y_preds = integrate_all(&y_preds, &y_train, d, s_d, s);
+        let mut y_preds = diff_all1d(&y, &[(d, 1), (s_d, s)]).slice(s![-from_end..]).to_owned();
+        y_preds = integrate_all(&y_preds, &y_train, &[(d, 1), (s_d, s)]);
         
         assert_eq!(y_future, y_preds);
     }
@@ -192,8 +200,8 @@ mod tests {
 
         let y_future = y.slice(s![cutoff..]).to_owned();
 
-        let mut y_preds = diff_all1d(&y, d, s_d, s).slice(s![-from_end..]).to_owned();
-        y_preds = integrate_all(&y_preds, &y_train, d, s_d, s);
+        let mut y_preds = diff_all1d(&y, &[(d, 1), (s_d, s)]).slice(s![-from_end..]).to_owned();
+        y_preds = integrate_all(&y_preds, &y_train, &[(d, 1), (s_d, s)]);
         
         assert_eq!(y_future, y_preds);
     }
@@ -211,9 +219,39 @@ mod tests {
 
         let y_future = y.slice(s![cutoff..]).to_owned();
 
-        let mut y_preds = diff_all1d(&y, d, s_d, s).slice(s![-from_end..]).to_owned();
-        y_preds = integrate_all(&y_preds, &y_train, d, s_d, s);
+        let mut y_preds = diff_all1d(&y, &[(d, 1), (s_d, s)]).slice(s![-from_end..]).to_owned();
+        y_preds = integrate_all(&y_preds, &y_train, &[(d, 1), (s_d, s)]);
         
         assert_eq!(y_future, y_preds);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn difference_integrate_two_seasonal_periods() {
+
+        let diffs = [(1, 1), (1, 3), (1, 4)];
+
+        let y: Array1<f64> = Array::range(0., 40., 1.).mapv(|t| t * t / 10. + [0., 3., 1.][t as usize % 3] + [2., 0., 1., 5.][t as usize % 4]);
+
+        let cutoff = 20;
+        let from_end = (y.len() - cutoff) as isize;
+        let y_train = y.slice(s![..cutoff]).to_owned();
+        let y_future = y.slice(s![cutoff..]).mapv(|v| (1e6 * v).round() / 1e6);
+
+        let mut y_preds = diff_all1d(&y, &diffs).slice(s![-from_end..]).to_owned();
+        y_preds = integrate_all(&y_preds, &y_train, &diffs);
+
+        assert_eq!(y_future, y_preds.mapv(|v| (1e6 * v).round() / 1e6));
+    }
+
+    #[test]
+    fn difference_fill_levels() {
+        let (d, s_d, s) = (1, 1, 3);
+        let y: Array1<f64> = arr1(&[1., 5., 2., 3., 7., 5., 4., 9., 7., 6., 11., 8.]);
+        let y_diff = diff_all1d(&y, &[(d, 1), (s_d, s)]);
+
+        let mut y_missing = y.clone();
+        y_missing[6] = f64::NAN;
+        y_missing[10] = f64::NAN;
+        assert_eq!(fill_levels(&y_missing, &y_diff, &[(d, 1), (s_d, s)]), y);
+    }
+}
@@ -0,0 +1,263 @@
+//! Parameter recovery suite on stored series in `tests/fixtures/recovery`.
+//!
+//! Each case has a `{case}.csv` with y and any exogenous variables x0, x1, ..., simulated once from a known
+//! data-generating process. The coefficients and σ² are estimated on the whole series, and forecasts
+//! `forecast.h1`, ..., `forecast.h12` of its last 12 observations from the others. `expected.csv` has one row
+//! per case, estimation method, source and coefficient or forecast:
+//! - dgp: the coefficients the series was simulated with and the forecasts with them
+//! - arima: estimates and forecasts of this crate when the fixtures were written, so that changes are noticed
+//!
+//! `reference.csv` has rows in the same format from other implementations, source R for `forecast::Arima`
+//! and statsmodels for `SARIMAX`, written by `reference.R` and `reference.py` in the same directory.
+//! Their estimates are maximum likelihood, so they are compared within the tolerance of the method.
+//! Every case they can fit must have rows from both, so that an empty reference.csv fails.
+//! Regenerate the series and expected.csv with `cargo test recovery_write_fixtures -- --ignored`,
+//! then reference.csv with the scripts.
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use numpy::ndarray::{Array, Array1, Array2, Axis, s};
+use super::{Exog, Method, Model, ModelBuilder, Noise, Regression, Trend};
+use crate::random::Rng;
+
+/// number of observations of each series
+const N: usize = 1200;
+/// horizons forecast from all but the last H observations
+const H: usize = 12;
+/// tolerance of the arima rows, loose enough for differences between linear algebra libraries
+const SNAPSHOT_TOLERANCE: f64 = 1e-4;
+/// estimation methods and the tolerance of their dgp rows for coefficients, that of σ² is 5% of it.
+/// Recursive estimation is biased on mixed ARMA models since the errors of its early, imprecise fits feed the MA columns.
+const METHODS: [(&str, Method, f64); 2] = [("recursive", Method::Recursive, 0.2), ("hannan-rissanen", Method::HannanRissanen, 0.1)];
+/// tolerance of the dgp forecasts relative to σ
+const FORECAST_TOLERANCE: f64 = 0.5;
+/// sources of reference.csv
+const REFERENCES: [&str; 2] = ["R", "statsmodels"];
+
+/// a data-generating process
+struct Case {
+    // name: of the case and its csv
+    // builder: model specification, without the estimation method
+    // params: coefficients in the order of coef_names
+    // sigma: standard deviation of the innovations
+    // n_exog: number of exogenous variables, simulated as random walks
+    // reference: whether R and statsmodels fit the same model, so that reference.csv has rows for it
+    name: &'static str,
+    builder: ModelBuilder,
+    params: Vec<f64>,
+    sigma: f64,
+    n_exog: usize,
+    reference: bool
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case {
+            name: "ma",
+            builder: ModelBuilder::new().order((0, 0, 2)),
+            params: vec![5., 0.6, -0.3],
+            sigma: 1.,
+            n_exog: 0,
+            reference: true
+        },
+        Case {
+            name: "arma",
+            builder: ModelBuilder::new().order((1, 0, 1)),
+            params: vec![1., 0.4, 0.5],
+            sigma: 2.,
+            n_exog: 0,
+            reference: true
+        },
+        Case {
+            name: "seasonal_ma",
+            builder: ModelBuilder::new().order((0, 1, 1)).seasonal_order((0, 1, 1, 12)),
+            params: vec![-0.4, -0.6],
+            sigma: 1.,
+            n_exog: 0,
+            reference: true
+        },
+        Case {
+            name: "differenced_drift",
+            builder: ModelBuilder::new().order((1, 1, 1)),
+            params: vec![0.2, -0.3, 0.5],
+            sigma: 1.,
+            n_exog: 0,
+            reference: true
+        },
+        Case {
+            name: "armax",
+            builder: ModelBuilder::new().order((1, 0, 1)).exog(Exog::Levels).exog_lags(vec![vec![0], vec![0, 1]]),
+            params: vec![2., 0.3, 0.6, 1.5, -0.5, 0.25],
+            sigma: 1.,
+            n_exog: 2,
+            // Arima and SARIMAX only fit exogenous variables as a regression with ARMA errors
+            reference: false
+        },
+        Case {
+            name: "sarima_errors",
+            builder: ModelBuilder::new().order((1, 1, 0)).trend(Trend::N).regression(Regression::SarimaErrors),
+            params: vec![0.5, 2.],
+            sigma: 1.,
+            n_exog: 1,
+            reference: true
+        }
+    ]
+}
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("recovery")
+}
+
+/// y and x of a case
+fn read_series(name: &str) -> (Array1<f64>, Array2<f64>) {
+    let rows = read_csv(&format!("{}.csv", name));
+    let values: Vec<Vec<f64>> = rows.iter().map(|row| row.iter().map(|v| v.parse().unwrap()).collect()).collect();
+    let data = Array2::from_shape_fn((values.len(), values[0].len()), |(i, j)| values[i][j]);
+    (data.column(0).to_owned(), data.slice(s![.., 1..]).to_owned())
+}
+
+/// a row of expected.csv
+struct Expected {
+    source: String,
+    name: String,
+    value: f64,
+    tolerance: f64
+}
+
+/// rows of expected.csv or reference.csv by (case, method)
+fn read_expected(file: &str) -> HashMap<(String, String), Vec<Expected>> {
+    let mut expected: HashMap<_, Vec<_>> = HashMap::new();
+    for row in read_csv(file) {
+        expected.entry((row[0].clone(), row[1].clone())).or_default().push(Expected {
+            source: row[2].clone(),
+            name: row[3].clone(),
+            value: row[4].parse().unwrap(),
+            tolerance: row[5].parse().unwrap()
+        });
+    }
+    expected
+}
+
+/// rows of a csv file without its header
+fn read_csv(file: &str) -> Vec<Vec<String>> {
+    let path = fixtures_dir().join(file);
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("Can't read {}: {}.", path.display(), e));
+    text.lines().skip(1).filter(|line| !line.is_empty()).map(|line| line.split(',').map(String::from).collect()).collect()
+}
+
+/// values by name of the coefficients, or params if given, followed by sigma2
+fn estimates(model: &Model, params: Option<&[f64]>) -> Vec<(String, f64)> {
+    let coefs = params.map_or_else(|| model.coefs.clone().unwrap().to_vec(), |params| params.to_vec());
+    model.coef_names().into_iter().zip(coefs).chain([("sigma2".to_string(), model.sigma2.unwrap())]).collect()
+}
+
+/// forecasts of the last H observations from a model fit to the others, or with params as its coefficients
+fn forecasts(case: &Case, method: Method, y: &Array1<f64>, x: &Array2<f64>, params: Option<&[f64]>) -> Vec<(String, f64)> {
+    let n = y.len() - H;
+    let (y_train, x_train) = (y.slice(s![..n]).to_owned(), x.slice(s![..n, ..]).to_owned());
+    let mut model = fit(case, method, &y_train, &x_train);
+    if let Some(params) = params {
+        model.coefs = Some(Array::from_vec(params.to_vec()));
+        model = model.apply(&y_train, Some(&x_train));
+    }
+    let preds = model.predict(H, Some(&x.slice(s![n.., ..]).to_owned()));
+    preds.iter().enumerate().map(|(k, &p)| (format!("forecast.h{}", k + 1), p)).collect()
+}
+
+fn fit(case: &Case, method: Method, y: &Array1<f64>, x: &Array2<f64>) -> Model {
+    let mut model = case.builder.clone().method(method).build();
+    model.fit(y, Some(x));
+    model
+}
+
+/// asserts that the estimates and forecasts of a case are within the tolerance of each row
+fn check(case: &Case, method_name: &str, method: Method, rows: &[Expected]) {
+    let (y, x) = read_series(case.name);
+    let estimates: HashMap<String, f64> = estimates(&fit(case, method, &y, &x), None).into_iter()
+        .chain(forecasts(case, method, &y, &x, None))
+        .collect();
+    for row in rows {
+        let estimate = *estimates.get(&row.name)
+            .unwrap_or_else(|| panic!("{} with {}: {} has no estimate named {}.", case.name, method_name, row.source, row.name));
+        assert!(
+            (estimate - row.value).abs() <= row.tolerance,
+            "{} with {}: {} is {}, {} expects {} ± {}.", case.name, method_name, row.name, estimate, row.source, row.value, row.tolerance
+        );
+    }
+}
+
+#[test]
+fn recovery_fixtures() {
+    let expected = read_expected("expected.csv");
+    for case in cases() {
+        for (method_name, method, _) in METHODS {
+            let rows = expected.get(&(case.name.to_string(), method_name.to_string()))
+                .unwrap_or_else(|| panic!("No expected values for {} with {}.", case.name, method_name));
+            check(&case, method_name, method, rows);
+        }
+    }
+}
+
+#[test]
+fn recovery_reference() {
+    let reference = read_expected("reference.csv");
+    for case in cases().into_iter().filter(|case| case.reference) {
+        for (method_name, method, _) in METHODS {
+            let rows = reference.get(&(case.name.to_string(), method_name.to_string())).map_or(&[][..], |rows| rows);
+            for source in REFERENCES {
+                if !rows.iter().any(|row| row.source == source) {
+                    panic!(
+                        "No {} reference values for {} with {}. Run reference.R and reference.py in tests/fixtures/recovery.",
+                        source, case.name, method_name
+                    );
+                }
+            }
+            check(&case, method_name, method, rows);
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn recovery_write_fixtures() {
+    let dir = fixtures_dir();
+    fs::create_dir_all(&dir).unwrap();
+    let mut expected = String::from("case,method,source,name,value,tolerance\n");
+    for (i, case) in cases().into_iter().enumerate() {
+        let mut rng = Rng::new(1000 + i as u64);
+        let mut x: Array2<f64> = Array::zeros((N, case.n_exog));
+        for mut col in x.axis_iter_mut(Axis(1)) {
+            col.assign(&Array::from_iter((0..N).scan(0., |level, _| {*level += rng.normal(); Some(*level)})));
+        }
+        let spec = case.builder.clone().build();
+        let y = spec.simulate(N, &Array::from_vec(case.params.clone()), case.sigma, Some(&x), Noise::Normal, i as u64, 200);
+
+        let header: Vec<String> = ["y".to_string()].into_iter().chain((0..case.n_exog).map(|j| format!("x{}", j))).collect();
+        let mut csv = header.join(",") + "\n";
+        for t in 0..N {
+            let row: Vec<String> = [y[t]].into_iter().chain(x.row(t).iter().copied()).map(|v| v.to_string()).collect();
+            writeln!(csv, "{}", row.join(",")).unwrap();
+        }
+        fs::write(dir.join(format!("{}.csv", case.name)), csv).unwrap();
+
+        for (method_name, method, tolerance) in METHODS {
+            let mut model = fit(&case, method, &y, &x);
+            let estimated = estimates(&model, None).into_iter().chain(forecasts(&case, method, &y, &x, None));
+            for (name, value) in estimated {
+                writeln!(expected, "{},{},arima,{},{},{}", case.name, method_name, name, value, SNAPSHOT_TOLERANCE).unwrap();
+            }
+            model.sigma2 = Some(case.sigma.powi(2));
+            let truth = estimates(&model, Some(&case.params)).into_iter().chain(forecasts(&case, method, &y, &x, Some(&case.params)));
+            for (name, value) in truth {
+                let tolerance = match name.as_str() {
+                    "sigma2" => 0.05 * value,
+                    _ if name.starts_with("forecast") => FORECAST_TOLERANCE * case.sigma,
+                    _ => tolerance
+                };
+                writeln!(expected, "{},{},dgp,{},{},{}", case.name, method_name, name, value, tolerance).unwrap();
+            }
+        }
+    }
+    fs::write(dir.join("expected.csv"), expected).unwrap();
+}
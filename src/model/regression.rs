@@ -0,0 +1,196 @@
+use numpy::ndarray::{Array, Array1, Array2, Axis, ShapeBuilder, arr1, concatenate, s};
+use super::{Model, ModelBuilder};
+use super::fit_predict::{complete_rows, solve_complete};
+use super::polynomial;
+
+
+/// maximum number of alternations between estimating β and the SARIMA errors
+const MAX_ITERATIONS: usize = 20;
+/// relative change of β below which the estimation has converged
+const TOLERANCE: f64 = 1e-8;
+
+impl Model {
+    /// regression with SARIMA errors, y_t = x_t β + η_t where η_t follows the SARIMA model with trend.
+    /// Starting from least squares of the differenced y on the differenced x, estimation alternates between
+    /// 1. fitting the SARIMA model to η = y - x β
+    /// 2. least squares of the differenced y on the differenced x and trend, all filtered by φ(B) / θ(B)
+    ///
+    /// With missing values in y or x the first least squares estimate of β is kept.
+    ///
+    /// returns the SARIMA coefficients followed by β, the one-step-ahead errors of η (NaN without a prediction),
+    /// σ² and the number of observations used for estimation
+    pub(super) fn fit_regression_errors(&self, exog_fit: &Array2<f64>, endog_fit: &Array1<f64>) -> (Array1<f64>, Array1<f64>, f64, usize) {
+
+        let y_diff = self.difference(endog_fit);
+        let x_diff = Array2::from_shape_vec(
+            (y_diff.len(), exog_fit.shape()[1]).f(),
+            exog_fit.columns().into_iter().flat_map(|col| self.difference(&col.to_owned())).collect()
+        ).unwrap();
+
+        let mut beta = self.gls(&y_diff, &x_diff, None);
+        let mut errors_model = self.errors_model();
+
+        let has_missing = endog_fit.iter().chain(exog_fit.iter()).any(|v| v.is_nan());
+        if !has_missing {
+            for _ in 0..MAX_ITERATIONS {
+                errors_model.fit(&(endog_fit - &exog_fit.dot(&beta)), None);
+
+                let new_beta = self.gls(&y_diff, &x_diff, errors_model.coefs.as_ref());
+                let change = (&new_beta - &beta).mapv(f64::abs).fold(0., |a: f64, &b| a.max(b));
+                let scale = 1. + beta.mapv(f64::abs).fold(0., |a: f64, &b| a.max(b));
+                beta = new_beta;
+                if change < TOLERANCE * scale {
+                    break;
+                }
+            }
+        }
+
+        errors_model.fit(&(endog_fit - &exog_fit.dot(&beta)), None);
+        let coefs = concatenate![Axis(0), errors_model.coefs.as_ref().unwrap().view(), beta.view()];
+        (coefs, errors_model.errors_fit.unwrap(), errors_model.sigma2.unwrap(), errors_model.nobs.unwrap())
+    }
+
+    /// returns the one-step-ahead errors of η with the coefficients from fit_regression_errors,
+    /// aligned with endog_fit and NaN without a prediction
+    pub(super) fn filter_regression_errors(&self, exog_fit: &Array2<f64>, endog_fit: &Array1<f64>, coefs: &Array1<f64>) -> Array1<f64> {
+        self.errors_model_with(exog_fit, endog_fit, coefs).filter()
+    }
+
+    /// returns predictions x_future β + forecasts of η with the coefficients from fit_regression_errors,
+    /// given the one-step-ahead errors of η if known
+    pub(super) fn predict_regression_errors(
+        &self,
+        h: usize,
+        exog_fit: &Array2<f64>,
+        exog_future: &Array2<f64>,
+        endog_fit: &Array1<f64>,
+        coefs: &Array1<f64>,
+        errors: Option<&Array1<f64>>
+    ) -> Array1<f64> {
+        let beta = coefs.slice(s![coefs.len() - exog_fit.shape()[1]..]);
+        let mut errors_model = self.errors_model_with(exog_fit, endog_fit, coefs);
+        errors_model.errors_fit = errors.cloned();
+        errors_model.predict(h, None) + exog_future.dot(&beta)
+    }
+
+    /// errors_model fit to η = y - x β with the SARIMA coefficients from fit_regression_errors
+    fn errors_model_with(&self, exog_fit: &Array2<f64>, endog_fit: &Array1<f64>, coefs: &Array1<f64>) -> Model {
+        let n_sarima = coefs.len() - exog_fit.shape()[1];
+        let mut errors_model = self.errors_model();
+        errors_model.endog_fit = Some(endog_fit - &exog_fit.dot(&coefs.slice(s![n_sarima..])));
+        errors_model.exog_fit = Some(Array::zeros((endog_fit.len(), 0)));
+        errors_model.coefs = Some(coefs.slice(s![..n_sarima]).to_owned());
+        errors_model
+    }
+
+    /// SARIMA model without exogenous variables for η, on the already transformed scale
+    fn errors_model(&self) -> Model {
+        let o = &self.order;
+        ModelBuilder::new()
+            .order((o.p, o.d, o.q))
+            .seasonal_orders(self.seasonal_orders.iter().map(|so| (so.p, so.d, so.q, so.s)).collect())
+            .seasonality(self.seasonality)
+            .trend(self.trend)
+            .allow_high_order_trend(true)
+            .method(self.method)
+            .future_errors(self.future_errors)
+            .missing(self.missing)
+            .enforce_stationarity(self.enforce_stationarity)
+            .enforce_invertibility(self.enforce_invertibility)
+            .build()
+    }
+
+    /// z differenced with d, D and s, without the first d + D * s observations
+    fn difference(&self, z: &Array1<f64>) -> Array1<f64> {
+        let diff = polynomial::diff_polynomial(&self.differences());
+        let nobs_lost = diff.len() - 1;
+        if nobs_lost >= z.len() {
+            panic!("y used for fitting is not long enough based on model specification.")
+        }
+        polynomial::filter(z, &diff, &arr1(&[1.])).slice(s![nobs_lost..]).to_owned()
+    }
+
+    /// β from least squares of the differenced y on the trend and differenced x, filtered by the
+    /// AR and MA polynomials of the SARIMA coefficients (none for ordinary least squares)
+    fn gls(&self, y_diff: &Array1<f64>, x_diff: &Array2<f64>, coefs: Option<&Array1<f64>>) -> Array1<f64> {
+        let (ar, ma) = match coefs {
+            Some(coefs) => (self.ar_polynomial(coefs), self.ma_polynomial(coefs)),
+            None => (arr1(&[1.]), arr1(&[1.]))
+        };
+        let nobs_lost = ar.len() - 1;
+        if nobs_lost >= y_diff.len() {
+            panic!("y used for fitting is not long enough based on model specification.")
+        }
+
+        let n_trend = self.trend.n_cols();
+        let trend = self.prepare_trend(y_diff.len(), y_diff.len(), 0);
+        let x = concatenate![Axis(1), trend.view(), x_diff.view()];
+
+        let mut x_filtered: Array2<f64> = Array::zeros(x.raw_dim());
+        for (j, col) in x.columns().into_iter().enumerate() {
+            x_filtered.column_mut(j).assign(&polynomial::filter(&col.to_owned(), &ar, &ma));
+        }
+        let y_filtered = polynomial::filter(y_diff, &ar, &ma);
+
+        let x_filtered = x_filtered.slice(s![nobs_lost.., ..]).to_owned();
+        let y_filtered = y_filtered.slice(s![nobs_lost..]).to_owned();
+        let complete = complete_rows(&x_filtered, &y_filtered);
+        let coefs = solve_complete(x_filtered.view(), y_filtered.view(), &complete);
+        coefs.slice(s![n_trend..]).to_owned()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Regression, Trend};
+
+    fn regressor(n: usize) -> Array2<f64> {
+        Array::from_iter((0..n).map(|t| (t as f64 / 3.).sin() * 4. + (t % 5) as f64))
+            .insert_axis(Axis(1))
+    }
+
+    #[test]
+    fn regression_ar_errors() {
+        // y = 10 + 3 x + η, η_t = 0.5 η_{t-1}
+        let n = 80;
+        let x = regressor(n + 10);
+        let mut eta: Array1<f64> = Array::zeros(n + 10);
+        eta[0] = 4.;
+        for t in 1..n + 10 {
+            eta[t] = 0.5 * eta[t - 1];
+        }
+        let y = &eta + 10. + &x.column(0).mapv(|v| 3. * v);
+
+        let mut model = ModelBuilder::new().order((1, 0, 0)).regression(Regression::SarimaErrors).build();
+        model.fit(&y.slice(s![..n]).to_owned(), Some(&x.slice(s![..n, ..]).to_owned()));
+        let y_preds = model.predict(10, Some(&x.slice(s![n.., ..]).to_owned()));
+
+        // intercept of the AR equation for 10 + η is 10 * (1 - 0.5)
+        let coefs = model.coefs.as_ref().unwrap().mapv(|c| (100. * c).round() / 100.);
+        assert_eq!(coefs, arr1(&[5., 0.5, 3.]));
+        assert_eq!(y_preds.mapv(|v| (100. * v).round() / 100.), y.slice(s![n..]).mapv(|v| (100. * v).round() / 100.));
+    }
+
+    #[test]
+    fn regression_effect_in_levels() {
+        // y = 2 x + η with η a line, i.e. an ARIMA(0,1,0) with drift 0.7
+        let n = 60;
+        let x = regressor(n + 5);
+        let eta = Array::range(0., (n + 5) as f64, 1.).mapv(|t| 50. + 0.7 * t);
+        let y = &eta + &x.column(0).mapv(|v| 2. * v);
+
+        let mut model = ModelBuilder::new()
+            .order((0, 1, 0))
+            .trend(Trend::C)
+            .regression(Regression::SarimaErrors)
+            .build();
+        model.fit(&y.slice(s![..n]).to_owned(), Some(&x.slice(s![..n, ..]).to_owned()));
+        let y_preds = model.predict(5, Some(&x.slice(s![n.., ..]).to_owned()));
+
+        let coefs = model.coefs.as_ref().unwrap().mapv(|c| (100. * c).round() / 100.);
+        assert_eq!(coefs, arr1(&[0.7, 2.]));
+        assert_eq!(y_preds.mapv(|v| (100. * v).round() / 100.), y.slice(s![n..]).mapv(|v| (100. * v).round() / 100.));
+    }
+}
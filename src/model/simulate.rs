@@ -0,0 +1,230 @@
+use std::str::FromStr;
+use numpy::ndarray::{Array, Array1, Array2, arr1, s};
+use super::{Model, Regression, Transform};
+use super::polynomial;
+use crate::random::Rng;
+
+
+/// Distribution of the innovations of a simulated series, scaled to standard deviation σ
+/// - Normal
+/// - StudentT(ν): Student's t with ν > 2 degrees of freedom, for heavier tails
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Noise {
+    Normal,
+    StudentT(f64)
+}
+
+impl FromStr for Noise {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match (s, s.parse::<f64>()) {
+            ("normal", _) => Ok(Noise::Normal),
+            (_, Ok(df)) if df > 2. && df.is_finite() => Ok(Noise::StudentT(df)),
+            _ => Err(format!("Unknown noise: {}. It should be normal or finite degrees of freedom of a Student's t above 2.", s))
+        }
+    }
+}
+
+impl Noise {
+    /// a draw with mean 0 and variance 1
+    fn draw(&self, rng: &mut Rng) -> f64 {
+        match *self {
+            Noise::Normal => rng.normal(),
+            Noise::StudentT(df) => rng.student_t(df) * ((df - 2.) / df).sqrt()
+        }
+    }
+}
+
+impl Model {
+    /// simulates a series from the model specification with the given coefficients, without fitting.
+    /// The differenced series starts from zero pre-sample values and innovations and is integrated from zero,
+    /// and the first burn_in observations are dropped so that it forgets the start.
+    /// - n: number of observations
+    /// - params: coefficients in the order of `coef_names` with the columns of x
+    /// - sigma: standard deviation of the innovations on the transformed, differenced scale
+    /// - x: exogenous variables, length n. They are zero during the burn-in.
+    /// - noise: distribution of the innovations
+    /// - seed: of the random number generator, the same seed gives the same series
+    /// - burn_in: number of observations simulated before those returned
+    #[allow(clippy::too_many_arguments)]
+    pub fn simulate(
+        &self,
+        n: usize,
+        params: &Array1<f64>,
+        sigma: f64,
+        x: Option<&Array2<f64>>,
+        noise: Noise,
+        seed: u64,
+        burn_in: usize
+    ) -> Array1<f64> {
+
+        let x = x.map_or_else(|| Array::zeros((n, 0)), |x| x.to_owned());
+        if x.shape()[0] != n {
+            panic!("x is length: {}. It should be length: {}.", x.shape()[0], n);
+        }
+        if !sigma.is_finite() || sigma < 0. {
+            panic!("sigma should be finite and not negative, got {}.", sigma);
+        }
+        if let Noise::StudentT(df) = noise {
+            if !df.is_finite() || df <= 2. {
+                panic!("Student's t noise needs finite degrees of freedom above 2 for a finite variance, got {}.", df);
+            }
+        }
+
+        let mut spec = self.clone();
+        spec.exog_fit = Some(x.clone());
+        spec.lambda = match self.transform {
+            Transform::None => None,
+            Transform::Log => Some(0.),
+            Transform::BoxCox(lambda) => Some(lambda),
+            _ => panic!("Simulation needs a fixed transformation, got {:?}.", self.transform)
+        };
+        let names = spec.coef_names();
+        if params.len() != names.len() {
+            panic!("params has {} coefficients. It should have {}: {}.", params.len(), names.len(), names.join(", "));
+        }
+
+        let z = spec.simulate_transformed(params, sigma, &x, noise, seed, burn_in);
+        spec.inverse_transform(&z, None)
+    }
+
+    /// simulated series on the transformed scale, from φ(B) w_t = trend_t + x_t β + θ(B) e_t
+    /// for the differenced series w, or with η in place of y for regression with SARIMA errors
+    fn simulate_transformed(&self, params: &Array1<f64>, sigma: f64, x: &Array2<f64>, noise: Noise, seed: u64, burn_in: usize) -> Array1<f64> {
+        let n = x.shape()[0];
+        let len = n + burn_in;
+        let mut rng = Rng::new(seed);
+        let innovations = Array::from_iter((0..len).map(|_| sigma * noise.draw(&mut rng)));
+        let one = arr1(&[1.]);
+
+        // θ(B) e_t, then the trend and exog terms of the differenced equation
+        let mut u = polynomial::filter(&innovations, &self.ma_polynomial(params), &one);
+        let mut col = 0;
+        if self.trend.has_constant() {
+            u += params[col];
+            col += 1;
+        }
+        if self.trend.has_time() {
+            // time counts from 1 at the first returned observation, as when fitting
+            let first = 1. - burn_in as f64;
+            u += &(Array::range(first, first + len as f64, 1.) * params[col]);
+        }
+        if self.regression == Regression::Armax {
+            let exog = self.exog_effect(&self.align_exog(x), params);
+            let mut tail = u.slice_mut(s![burn_in..]);
+            tail += &exog;
+        }
+
+        let w = polynomial::filter(&u, &one, &self.ar_polynomial(params));
+        let z = polynomial::filter(&w, &one, &polynomial::diff_polynomial(&self.differences()));
+        let mut z = z.slice(s![burn_in..]).to_owned();
+        if self.regression == Regression::SarimaErrors {
+            z += &self.exog_effect(x, params);
+        }
+        z
+    }
+
+    /// exog with the columns chosen by the Exog setting differenced and zero where differencing loses rows
+    fn align_exog(&self, x: &Array2<f64>) -> Array2<f64> {
+        let n = x.shape()[0];
+        let mut aligned = x.to_owned();
+        if n <= self.nobs_differenced() {
+            aligned.fill(0.);
+            return aligned;
+        }
+        let diffed = self.difference_exog(x);
+        let nobs_lost = n - diffed.shape()[0];
+        for j in 0..x.shape()[1] {
+            if self.exog.is_differenced(j) {
+                aligned.slice_mut(s![..nobs_lost, j]).fill(0.);
+                aligned.slice_mut(s![nobs_lost.., j]).assign(&diffed.column(j));
+            }
+        }
+        aligned
+    }
+
+    /// Σ β_jl x_j,t-l over exogenous variables j and their lags l, with x zero before the first row
+    fn exog_effect(&self, x: &Array2<f64>, params: &Array1<f64>) -> Array1<f64> {
+        let n = x.shape()[0];
+        let n_betas: usize = (0..x.shape()[1]).map(|j| self.exog_lags(j).len()).sum();
+        let mut col = params.len() - n_betas;
+        let mut effect: Array1<f64> = Array::zeros(n);
+        for j in 0..x.shape()[1] {
+            for lag in self.exog_lags(j) {
+                for t in lag..n {
+                    effect[t] += params[col] * x[[t - lag, j]];
+                }
+                col += 1;
+            }
+        }
+        effect
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ModelBuilder, Exog};
+    use numpy::ndarray::{Axis, arr2};
+
+    #[test]
+    fn simulate_deterministic() {
+        // without noise (1 - B) y_t = 2 + 0.5 x_t starting from zero
+        let x = arr2(&[[0.], [2.], [0.], [0.]]);
+        let model = ModelBuilder::new().order((0, 1, 0)).trend("c".parse().unwrap()).exog(Exog::Levels).build();
+        let y = model.simulate(4, &arr1(&[2., 0.5]), 0., Some(&x), Noise::Normal, 0, 0);
+        assert_eq!(y, arr1(&[2., 5., 7., 9.]));
+
+        // AR(1) burnt in to its mean c / (1 - φ)
+        let y = Model::autoregressive(1).simulate(3, &arr1(&[1., 0.5]), 0., None, Noise::Normal, 0, 200);
+        assert!(y.iter().all(|v| (v - 2.).abs() < 1e-12));
+    }
+
+    #[test]
+    fn simulate_reproducible() {
+        let model = Model::sarima((1, 0, 1), (0, 1, 1, 4));
+        let params = arr1(&[0.1, 0.3, -0.4, 0.5]);
+        let y = model.simulate(50, &params, 1., None, Noise::Normal, 9, 20);
+        assert_eq!(y, model.simulate(50, &params, 1., None, Noise::Normal, 9, 20));
+        assert_ne!(y, model.simulate(50, &params, 1., None, Noise::Normal, 10, 20));
+    }
+
+    #[test]
+    fn simulate_recovery() {
+        let mut model = Model::arma(1, 1);
+        let y = model.simulate(5000, &arr1(&[1., 0.6, 0.3]), 2., None, Noise::StudentT(5.), 1, 100);
+        model.fit(&y, None);
+        let coefs = model.coefs.as_ref().unwrap();
+        assert!((coefs[1] - 0.6).abs() < 0.05 && (coefs[2] - 0.3).abs() < 0.05);
+        assert!((model.residuals().slice(s![1..]).var_axis(Axis(0), 0.)[()] / 4. - 1.).abs() < 0.1);
+    }
+
+    #[test]
+    fn simulate_regression_errors() {
+        let x = Array::from_shape_fn((100, 1), |(t, _)| t as f64);
+        let model = ModelBuilder::new().order((1, 0, 0)).regression(Regression::SarimaErrors).build();
+        let y = model.simulate(100, &arr1(&[0., 0.5, 3.]), 0., Some(&x), Noise::Normal, 0, 0);
+        assert_eq!(y, x.column(0).mapv(|v| 3. * v));
+    }
+
+    #[test]
+    #[should_panic(expected = "It should have 3: intercept, ar.L1, x0.")]
+    fn simulate_wrong_params() {
+        Model::autoregressive(1).simulate(10, &arr1(&[0., 0.5]), 1., Some(&Array::zeros((10, 1))), Noise::Normal, 0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "finite degrees of freedom above 2 for a finite variance, got NaN.")]
+    fn simulate_noise_nan() {
+        Model::autoregressive(1).simulate(10, &arr1(&[0., 0.5]), 1., None, Noise::StudentT(f64::NAN), 0, 0);
+    }
+
+    #[test]
+    fn simulate_noise_parse() {
+        assert_eq!("normal".parse::<Noise>().unwrap(), Noise::Normal);
+        assert_eq!("4".parse::<Noise>().unwrap(), Noise::StudentT(4.));
+        assert!("cauchy".parse::<Noise>().is_err());
+        assert!(["nan", "inf", "2"].iter().all(|s| s.parse::<Noise>().is_err()));
+    }
+}
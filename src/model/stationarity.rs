@@ -0,0 +1,211 @@
+use std::f64::consts::PI;
+use numpy::Complex64;
+use numpy::ndarray::{Array1, s};
+use super::{Model, Seasonality};
+
+
+/// roots of a_0 + a_1 z + ... + a_n z^n with the [Durand-Kerner method](https://en.wikipedia.org/wiki/Durand%E2%80%93Kerner_method)
+pub(super) fn roots(a: &[f64]) -> Vec<Complex64> {
+    let n = a.iter().rposition(|c| c.abs() > 1e-12).unwrap_or(0);
+    if n == 0 {
+        return Vec::new();
+    }
+    let lead = a[n];
+    let eval = |z: Complex64| a[..=n].iter().rev().fold(Complex64::new(0., 0.), |acc, &c| acc * z + c) / lead;
+
+    // all roots lie within Cauchy's bound
+    let radius = 1. + a[..n].iter().map(|c| (c / lead).abs()).fold(0., f64::max);
+    let mut z: Vec<Complex64> = (0..n).map(|k| Complex64::from_polar(radius, 2. * PI * k as f64 / n as f64 + 0.4)).collect();
+    for _ in 0..1000 {
+        let mut change: f64 = 0.;
+        for i in 0..n {
+            let denominator = (0..n).filter(|&j| j != i).fold(Complex64::new(1., 0.), |acc, j| acc * (z[i] - z[j]));
+            let delta = eval(z[i]) / denominator;
+            z[i] -= delta;
+            change = change.max(delta.norm() / (1. + z[i].norm()));
+        }
+        if change < 1e-15 {
+            break;
+        }
+    }
+    z
+}
+
+/// the s values of z with z^s = w
+fn seasonal_roots(w: Complex64, s: usize) -> Vec<Complex64> {
+    let (r, theta) = w.to_polar();
+    (0..s).map(|k| Complex64::from_polar(r.powf(1. / s as f64), (theta + 2. * PI * k as f64) / s as f64)).collect()
+}
+
+/// coefficients φ of a stationary AR polynomial 1 - φ_1 B - ... from unconstrained values, mapping them to partial
+/// autocorrelations in (-1, 1) as in [Monahan (1984)](https://doi.org/10.1093/biomet/71.2.403) and statsmodels,
+/// then to φ with the Durbin-Levinson recursion of [Jones (1980)](https://doi.org/10.1080/00401706.1980.10486171)
+fn constrain(u: &[f64]) -> Vec<f64> {
+    let n = u.len();
+    let r: Vec<f64> = u.iter().map(|x| x / (1. + x * x).sqrt()).collect();
+    let mut y = vec![vec![0.; n]; n];
+    for k in 0..n {
+        for i in 0..k {
+            y[k][i] = y[k - 1][i] + r[k] * y[k - 1][k - i - 1];
+        }
+        y[k][k] = r[k];
+    }
+    y.last().map_or(Vec::new(), |y| y.iter().map(|v| -v).collect())
+}
+
+/// inverse of constrain, with non-finite values when the polynomial isn't stationary
+fn unconstrain(phi: &[f64]) -> Vec<f64> {
+    let n = phi.len();
+    let mut y = vec![vec![0.; n]; n];
+    if n == 0 {
+        return Vec::new();
+    }
+    y[n - 1] = phi.iter().map(|v| -v).collect();
+    for k in (1..n).rev() {
+        for i in 0..k {
+            y[k - 1][i] = (y[k][i] - y[k][k] * y[k][k - i - 1]) / (1. - y[k][k] * y[k][k]);
+        }
+    }
+    (0..n).map(|k| y[k][k] / (1. - y[k][k] * y[k][k]).sqrt()).collect()
+}
+
+/// # Stationarity and invertibility
+///
+/// Roots are those of each non-seasonal and seasonal factor with multiplicative seasonality,
+/// which together are the roots of the product, and of the whole polynomial with additive seasonality.
+/// Available once fit has estimated the coefficients.
+impl Model {
+    /// roots of the AR polynomial of the differenced series
+    pub fn ar_roots(&self) -> Vec<Complex64> {
+        let coefs = self.coefs.as_ref().expect("Model must be fit before roots");
+        match self.seasonality {
+            Seasonality::Multiplicative => self.factor_roots(self.lag_cols(), coefs, -1.),
+            Seasonality::Additive => roots(&self.ar_polynomial(coefs).to_vec())
+        }
+    }
+
+    /// roots of the MA polynomial of the differenced series
+    pub fn ma_roots(&self) -> Vec<Complex64> {
+        let coefs = self.coefs.as_ref().expect("Model must be fit before roots");
+        match self.seasonality {
+            Seasonality::Multiplicative => self.factor_roots(self.error_cols(), coefs, 1.),
+            Seasonality::Additive => roots(&self.ma_polynomial(coefs).to_vec())
+        }
+    }
+
+    /// whether all roots of the AR polynomial lie outside the unit circle
+    pub fn is_stationary(&self) -> bool {
+        self.ar_roots().iter().all(|r| r.norm() > 1.)
+    }
+
+    /// whether all roots of the MA polynomial lie outside the unit circle
+    pub fn is_invertible(&self) -> bool {
+        self.ma_roots().iter().all(|r| r.norm() > 1.)
+    }
+
+    /// roots of 1 + sign * (c_1 w + c_2 w^2 + ...) with w = B^s for each factor
+    fn factor_roots(&self, cols: Vec<(usize, usize, usize)>, coefs: &Array1<f64>, sign: f64) -> Vec<Complex64> {
+        cols.into_iter()
+            .flat_map(|(start_col, end_col, s)| {
+                let poly: Vec<f64> = [1.].into_iter().chain(coefs.slice(s![start_col..end_col]).iter().map(|c| sign * c)).collect();
+                roots(&poly).into_iter().flat_map(move |w| seasonal_roots(w, s))
+            })
+            .collect()
+    }
+
+    /// whether the AR or MA coefficients are estimated with constraints
+    pub(super) fn is_constrained(&self) -> bool {
+        self.enforce_stationarity || self.enforce_invertibility
+    }
+
+    /// AR and MA coefficients, as in the columns between the trend and exogenous variables, from unconstrained
+    /// parameters. Each factor is transformed to be stationary or invertible when enforced.
+    pub(super) fn constrain_arma(&self, params: &Array1<f64>) -> Array1<f64> {
+        self.transform_arma(params, |values, sign| constrain(&values.iter().map(|v| sign * v).collect::<Vec<f64>>()).into_iter().map(|v| sign * v).collect())
+    }
+
+    /// inverse of constrain_arma, starting factors that are not stationary or invertible from zero
+    pub(super) fn unconstrain_arma(&self, arma: &Array1<f64>) -> Array1<f64> {
+        self.transform_arma(arma, |values, sign| {
+            let u = unconstrain(&values.iter().map(|v| sign * v).collect::<Vec<f64>>());
+            if u.iter().all(|v| v.is_finite()) {u} else {vec![0.; u.len()]}
+        })
+    }
+
+    /// applies transform(values, sign) to the values of each constrained factor, with sign -1 for MA factors
+    /// so that 1 + θ B is treated like 1 - φ B
+    fn transform_arma(&self, values: &Array1<f64>, transform: impl Fn(&[f64], f64) -> Vec<f64>) -> Array1<f64> {
+        let offset = self.trend.n_cols();
+        let mut result = values.to_owned();
+        let factors = self.error_cols().into_iter().map(|cols| (cols, -1., self.enforce_invertibility))
+            .chain(self.lag_cols().into_iter().map(|cols| (cols, 1., self.enforce_stationarity)));
+        for ((start_col, end_col, _), sign, enforce) in factors {
+            if enforce {
+                let (start, end) = (start_col - offset, end_col - offset);
+                let transformed = transform(&values.slice(s![start..end]).to_vec(), sign);
+                result.slice_mut(s![start..end]).assign(&Array1::from_vec(transformed));
+            }
+        }
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Model, ModelBuilder, Noise};
+    use numpy::ndarray::arr1;
+
+    fn sorted_norms(roots: Vec<Complex64>) -> Vec<f64> {
+        let mut norms: Vec<f64> = roots.iter().map(|r| (1e6 * r.norm()).round() / 1e6).collect();
+        norms.sort_by(f64::total_cmp);
+        norms
+    }
+
+    #[test]
+    fn stationarity_roots() {
+        // (1 - z)(1 - 0.5 z) = 1 - 1.5 z + 0.5 z^2
+        assert_eq!(sorted_norms(roots(&[1., -1.5, 0.5])), vec![1., 2.]);
+        // 1 + z^2 has roots ±i
+        let r = roots(&[1., 0., 1.]);
+        assert_eq!(sorted_norms(r.clone()), vec![1., 1.]);
+        assert!(r.iter().all(|r| r.re.abs() < 1e-9));
+        assert!(roots(&[1., 0.]).is_empty());
+    }
+
+    #[test]
+    fn stationarity_constrain_round_trip() {
+        let phi = constrain(&[0.3, -2., 1.5]);
+        let poly: Vec<f64> = [1.].into_iter().chain(phi.iter().map(|v| -v)).collect();
+        assert!(roots(&poly).iter().all(|r| r.norm() > 1.));
+        assert_eq!(unconstrain(&phi).iter().map(|v| (1e9 * v).round() / 1e9).collect::<Vec<f64>>(), vec![0.3, -2., 1.5]);
+        assert!(unconstrain(&[1.2]).iter().any(|v| !v.is_finite()));
+    }
+
+    #[test]
+    fn stationarity_model_roots() {
+        let mut model = Model::sarima((1, 0, 1), (1, 0, 0, 4));
+        // intercept, ma.L1, ar.L1, ar.S.L4
+        model.coefs = Some(arr1(&[0., 0.5, 0.5, 1. / 16.]));
+        assert_eq!(sorted_norms(model.ar_roots()), vec![2., 2., 2., 2., 2.]);
+        assert_eq!(sorted_norms(model.ma_roots()), vec![2.]);
+        assert!(model.is_stationary());
+        assert!(model.is_invertible());
+
+        model.coefs = Some(arr1(&[0., 1.5, 1.1, 0.]));
+        assert!(!model.is_stationary());
+        assert!(!model.is_invertible());
+    }
+
+    #[test]
+    fn stationarity_enforced() {
+        // a random walk estimated as an AR(1) is kept just inside the stationary region
+        let y = Model::arima(0, 1, 0).simulate(200, &arr1(&[0.05]), 0.6, None, Noise::Normal, 3, 0);
+
+        let mut model = ModelBuilder::new().order((1, 0, 1)).enforce_stationarity(true).enforce_invertibility(true).build();
+        model.fit(&y, None);
+        assert!(model.is_stationary());
+        assert!(model.is_invertible());
+    }
+}
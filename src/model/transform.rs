@@ -0,0 +1,223 @@
+use std::str::FromStr;
+use numpy::ndarray::{Array, Array1};
+use super::Model;
+
+
+/// Transformation of y applied before differencing and inverted after integration
+/// - None
+/// - Log
+/// - BoxCox(λ): [Box-Cox](https://otexts.com/fpp3/transformations.html) with a fixed λ
+/// - Guerrero: Box-Cox with λ chosen by Guerrero's method, which minimises the coefficient of variation
+///   of the ratio of standard deviation to mean^(1 - λ) across seasons (or pairs of observations without seasonality)
+/// - Loglik: Box-Cox with λ maximising the profile log-likelihood of the transformed series
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Transform {
+    None,
+    Log,
+    BoxCox(f64),
+    Guerrero,
+    Loglik
+}
+
+impl FromStr for Transform {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Transform::None),
+            "log" => Ok(Transform::Log),
+            "guerrero" => Ok(Transform::Guerrero),
+            "loglik" => Ok(Transform::Loglik),
+            _ => s.parse::<f64>().map(Transform::BoxCox).map_err(|_| {
+                format!("Unknown transform: {}. It should be one of: none, log, guerrero, loglik or a Box-Cox lambda.", s)
+            })
+        }
+    }
+}
+
+/// λ searched between these bounds when chosen automatically
+const LAMBDA_BOUNDS: (f64, f64) = (-1., 2.);
+
+/// y: time series, must be positive
+/// lambda: 0 is a log transformation
+fn box_cox(y: &Array1<f64>, lambda: f64) -> Array1<f64> {
+    if lambda == 0. {
+        y.mapv(f64::ln)
+    } else {
+        y.mapv(|v| (v.powf(lambda) - 1.) / lambda)
+    }
+}
+
+/// z: transformed time series
+/// lambda: 0 is a log transformation
+fn inv_box_cox(z: &Array1<f64>, lambda: f64) -> Array1<f64> {
+    if lambda == 0. {
+        z.mapv(f64::exp)
+    } else {
+        z.mapv(|v| (lambda * v + 1.).max(0.).powf(1. / lambda))
+    }
+}
+
+/// back-transformed mean rather than median, using a second order Taylor expansion
+/// z: transformed forecasts
+/// variance: forecast variance of z
+/// lambda: 0 is a log transformation
+fn inv_box_cox_bias_adjusted(z: &Array1<f64>, variance: &Array1<f64>, lambda: f64) -> Array1<f64> {
+    let adjustment = if lambda == 0. {
+        variance.mapv(|v| 1. + v / 2.)
+    } else {
+        Array::from_iter(z.iter().zip(variance.iter()).map(|(z_i, v)| {
+            1. + v * (1. - lambda) / (2. * (lambda * z_i + 1.).powi(2))
+        }))
+    };
+    inv_box_cox(z, lambda) * adjustment
+}
+
+/// λ minimising f over LAMBDA_BOUNDS with a grid search followed by a golden-section search
+fn minimise(f: impl Fn(f64) -> f64) -> f64 {
+    let (lower, upper) = LAMBDA_BOUNDS;
+    let step = 0.1;
+    let best = Array::range(lower, upper + step / 2., step)
+        .into_iter()
+        .min_by(|a, b| f(*a).total_cmp(&f(*b)))
+        .unwrap();
+
+    let ratio = (5f64.sqrt() - 1.) / 2.;
+    let (mut a, mut b) = ((best - step).max(lower), (best + step).min(upper));
+    while b - a > 1e-6 {
+        let c = b - ratio * (b - a);
+        let d = a + ratio * (b - a);
+        if f(c) < f(d) {b = d} else {a = c}
+    }
+    (a + b) / 2.
+}
+
+fn mean_std(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.);
+    (mean, variance.sqrt())
+}
+
+/// [Guerrero (1993)](https://doi.org/10.1002/for.3980120104)
+/// y: time series, missing values (NaN) are skipped within their sub-series so that seasons stay aligned
+/// period: length of the sub-series, the seasonal periodicity or 2 without seasonality
+fn guerrero(y: &[f64], period: usize) -> f64 {
+    let stats: Vec<(f64, f64)> = y
+        .chunks_exact(period)
+        .map(|chunk| chunk.iter().copied().filter(|v| !v.is_nan()).collect::<Vec<f64>>())
+        .filter(|observed| observed.len() >= 2)
+        .map(|observed| mean_std(&observed))
+        .collect();
+    if stats.len() < 2 {
+        panic!("y is not long enough to choose the Box-Cox lambda with Guerrero's method.");
+    }
+    minimise(|lambda| {
+        let ratios: Vec<f64> = stats.iter().map(|(mean, std)| std / mean.powf(1. - lambda)).collect();
+        let (mean, std) = mean_std(&ratios);
+        std / mean
+    })
+}
+
+/// λ maximising the profile log-likelihood of an i.i.d. normal model for the transformed series
+/// y: time series without missing values
+fn loglik(y: &[f64]) -> f64 {
+    let y = Array1::from_vec(y.to_vec());
+    let n = y.len() as f64;
+    let sum_log = y.mapv(f64::ln).sum();
+    minimise(|lambda| {
+        let z = box_cox(&y, lambda);
+        let variance = z.var(0.);
+        n / 2. * variance.ln() - (lambda - 1.) * sum_log
+    })
+}
+
+impl Model {
+    /// resolves λ for the transformation of y, ignoring missing values
+    pub(super) fn fit_transform(&mut self, y: &Array1<f64>) {
+        let observed: Vec<f64> = y.iter().copied().filter(|v| !v.is_nan()).collect();
+        if self.transform != Transform::None && observed.iter().any(|&v| v <= 0.) {
+            panic!("y must be positive to use a {:?} transformation.", self.transform);
+        }
+        let period = self.seasonal_orders.first().map_or(0, |so| so.s).max(2);
+        self.lambda = match self.transform {
+            Transform::None => None,
+            Transform::Log => Some(0.),
+            Transform::BoxCox(lambda) => Some(lambda),
+            Transform::Guerrero => Some(guerrero(&y.to_vec(), period)),
+            Transform::Loglik => Some(loglik(&observed))
+        };
+    }
+
+    pub(super) fn transform_y(&self, y: &Array1<f64>) -> Array1<f64> {
+        match self.lambda {
+            None => y.to_owned(),
+            Some(lambda) => box_cox(y, lambda)
+        }
+    }
+
+    /// returns predictions on the original scale, the mean when bias adjustment is enabled and the median otherwise
+    /// - z: predictions on the transformed scale
+    /// - variance: forecast variance on the transformed scale
+    pub(super) fn inverse_transform(&self, z: &Array1<f64>, variance: Option<&Array1<f64>>) -> Array1<f64> {
+        match (self.lambda, variance) {
+            (None, _) => z.to_owned(),
+            (Some(lambda), Some(variance)) if self.bias_adjust => inv_box_cox_bias_adjusted(z, variance, lambda),
+            (Some(lambda), _) => inv_box_cox(z, lambda)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::arr1;
+
+    #[test]
+    fn transform_box_cox_round_trip() {
+        let y = arr1(&[1., 2.5, 10., 40.]);
+        for lambda in [0., 0.5, 1., -0.5] {
+            let z = box_cox(&y, lambda);
+            assert_eq!(inv_box_cox(&z, lambda).mapv(|v| (1e6 * v).round() / 1e6), y);
+        }
+        assert_eq!(box_cox(&y, 1.), &y - 1.);
+    }
+
+    #[test]
+    fn transform_bias_adjusted_log() {
+        // mean of a log-normal distribution is exp(μ + σ^2 / 2) ≈ exp(μ) (1 + σ^2 / 2)
+        let z = arr1(&[1., 2.]);
+        let variance = arr1(&[0.01, 0.02]);
+        let result = inv_box_cox_bias_adjusted(&z, &variance, 0.);
+        assert_eq!(result, arr1(&[1f64.exp() * 1.005, 2f64.exp() * 1.01]));
+    }
+
+    #[test]
+    fn transform_guerrero_multiplicative() {
+        // standard deviation proportional to the level is stabilised by a log transformation
+        let pattern = [0.8, 1.2, 0.9, 1.1];
+        let y: Vec<f64> = (0..48).map(|t| 10. * 1.05f64.powi(t) * pattern[t as usize % 4]).collect();
+        let lambda = guerrero(&y, 4);
+        assert_eq!((10. * lambda).round() / 10., 0.);
+
+        // a gap doesn't shift the later seasons
+        let mut gaps = y.clone();
+        gaps[5] = f64::NAN;
+        gaps[22] = f64::NAN;
+        assert!((guerrero(&gaps, 4) - lambda).abs() < 0.05);
+    }
+
+    #[test]
+    fn transform_loglik_log_normal() {
+        let y: Vec<f64> = (1..200).map(|t| (t as f64 / 20.).exp() * (1. + 0.1 * (t as f64).sin())).collect();
+        let lambda = loglik(&y);
+        assert!(lambda.abs() < 0.2);
+    }
+
+    #[test]
+    fn transform_from_str() {
+        assert_eq!("log".parse::<Transform>().unwrap(), Transform::Log);
+        assert_eq!("0.5".parse::<Transform>().unwrap(), Transform::BoxCox(0.5));
+        assert!("sqrt".parse::<Transform>().is_err());
+    }
+}
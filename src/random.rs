@@ -0,0 +1,169 @@
+use numpy::ndarray::{Array1, Array2};
+use crate::model::normal_quantile;
+
+
+/// Pseudo-random numbers from [xoshiro256**](https://prng.di.unimi.it/), seeded with SplitMix64,
+/// so that simulations are reproducible from a seed without extra dependencies.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Rng {
+    state: [u64; 4]
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let mut split_mix = || {
+            x = x.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        };
+        Self {state: [split_mix(), split_mix(), split_mix(), split_mix()]}
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// uniform on (0, 1), never exactly 0 or 1
+    pub fn uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    /// standard normal, from the inverse of its cumulative distribution function
+    pub fn normal(&mut self) -> f64 {
+        normal_quantile(self.uniform())
+    }
+
+    /// Gamma(shape, 1) with the method of [Marsaglia and Tsang (2000)](https://doi.org/10.1145/358407.358414)
+    pub fn gamma(&mut self, shape: f64) -> f64 {
+        if !shape.is_finite() || shape <= 0. {
+            panic!("shape should be positive and finite, got {}.", shape);
+        }
+        if shape < 1. {
+            // Gamma(shape) = Gamma(shape + 1) U^(1 / shape)
+            return self.gamma(shape + 1.) * self.uniform().powf(1. / shape);
+        }
+        let d = shape - 1. / 3.;
+        let c = 1. / (9. * d).sqrt();
+        loop {
+            let z = self.normal();
+            let v = (1. + c * z).powi(3);
+            if v > 0. && self.uniform().ln() < z * z / 2. + d - d * v + d * v.ln() {
+                return d * v;
+            }
+        }
+    }
+
+    /// Student's t with df degrees of freedom
+    pub fn student_t(&mut self, df: f64) -> f64 {
+        if !df.is_finite() || df <= 0. {
+            panic!("df should be positive and finite, got {}.", df);
+        }
+        self.normal() / (2. * self.gamma(df / 2.) / df).sqrt()
+    }
+
+    /// index i with probability weights[i] / Σ weights
+    pub fn choose(&mut self, weights: &Array1<f64>) -> usize {
+        let u = self.uniform() * weights.sum();
+        let mut cumulative = 0.;
+        for (i, w) in weights.iter().enumerate() {
+            cumulative += w;
+            if u < cumulative {
+                return i;
+            }
+        }
+        weights.len() - 1
+    }
+}
+
+/// n simulated paths mean_k + Σ_j≤k ψ_j e_k-j of h horizons with independent normal innovations e of variance σ²,
+/// one row per path, so that the horizons of a path are correlated like the errors of the forecasts
+pub(crate) fn sample_paths(mean: &Array1<f64>, psi: &Array1<f64>, sigma2: f64, n: usize, rng: &mut Rng) -> Array2<f64> {
+    let (h, sigma) = (mean.len(), sigma2.sqrt());
+    let mut paths = Array2::zeros((n, h));
+    for mut path in paths.rows_mut() {
+        let innovations = Array1::from_iter((0..h).map(|_| sigma * rng.normal()));
+        for k in 0..h {
+            path[k] = mean[k] + (0..=k).map(|j| psi[j] * innovations[k - j]).sum::<f64>();
+        }
+    }
+    paths
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::arr1;
+
+    #[test]
+    fn random_reproducible() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+        assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+    }
+
+    #[test]
+    fn random_moments() {
+        let mut rng = Rng::new(1);
+        let n = 100_000;
+        let uniform: Vec<f64> = (0..n).map(|_| rng.uniform()).collect();
+        assert!(uniform.iter().all(|&u| u > 0. && u < 1.));
+        assert!((uniform.iter().sum::<f64>() / n as f64 - 0.5).abs() < 0.01);
+
+        let normal: Vec<f64> = (0..n).map(|_| rng.normal()).collect();
+        let mean = normal.iter().sum::<f64>() / n as f64;
+        let variance = normal.iter().map(|z| (z - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!(mean.abs() < 0.02);
+        assert!((variance - 1.).abs() < 0.02);
+    }
+
+    #[test]
+    fn random_gamma_and_t() {
+        let mut rng = Rng::new(3);
+        let n = 100_000;
+        for shape in [0.5, 3.] {
+            let mean = (0..n).map(|_| rng.gamma(shape)).sum::<f64>() / n as f64;
+            assert!((mean / shape - 1.).abs() < 0.02);
+        }
+        // variance df / (df - 2)
+        let variance = (0..n).map(|_| rng.student_t(5.).powi(2)).sum::<f64>() / n as f64;
+        assert!((variance / (5. / 3.) - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    #[should_panic(expected = "df should be positive and finite, got inf.")]
+    fn random_student_t_infinite() {
+        Rng::new(3).student_t(f64::INFINITY);
+    }
+
+    #[test]
+    fn random_sample_paths() {
+        // random walk paths: steps are the innovations and the variance grows with the horizon
+        let paths = sample_paths(&arr1(&[1., 1.]), &arr1(&[1., 1.]), 4., 50_000, &mut Rng::new(2));
+        let steps = paths.column(1).to_owned() - paths.column(0);
+        assert!((steps.var(0.) / 4. - 1.).abs() < 0.05);
+        assert!((paths.column(1).var(0.) / 8. - 1.).abs() < 0.05);
+        assert!((paths.column(1).mean().unwrap() - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    fn random_choose() {
+        let mut rng = Rng::new(2);
+        let weights = arr1(&[0.2, 0., 0.8]);
+        let draws: Vec<usize> = (0..10_000).map(|_| rng.choose(&weights)).collect();
+        assert!(!draws.contains(&1));
+        assert!((draws.iter().filter(|&&i| i == 2).count() as f64 / 10_000. - 0.8).abs() < 0.02);
+    }
+}
@@ -0,0 +1,77 @@
+"""Estimates and forecasts of statsmodels' SARIMAX for the recovery cases, written to reference.csv with source statsmodels.
+
+Run from this directory with `python reference.py`; it replaces the rows of its own source only.
+
+SARIMAX puts the trend in the differenced equation, φ(B) w_t = c + θ(B) e_t, so its intercept is that of this crate.
+Its exogenous variables are a regression with SARIMA errors, which is the sarima_errors case, whereas armax has them
+next to the lags of y. No SARIMAX specification fits that model, so armax is left out, as in recovery.rs.
+"""
+import csv
+import os
+
+import numpy as np
+import pandas as pd
+from statsmodels.tsa.statespace.sarimax import SARIMAX
+
+H = 12
+# coefficient tolerance by estimation method of this crate, as in recovery.rs
+METHODS = {"recursive": 0.2, "hannan-rissanen": 0.1}
+
+CASES = {
+    "ma": dict(order=(0, 0, 2), seasonal_order=(0, 0, 0, 0), trend="c", exog=False),
+    "arma": dict(order=(1, 0, 1), seasonal_order=(0, 0, 0, 0), trend="c", exog=False),
+    "seasonal_ma": dict(order=(0, 1, 1), seasonal_order=(0, 1, 1, 12), trend="n", exog=False),
+    "differenced_drift": dict(order=(1, 1, 1), seasonal_order=(0, 0, 0, 0), trend="c", exog=False),
+    "sarima_errors": dict(order=(1, 1, 0), seasonal_order=(0, 0, 0, 0), trend="n", exog=True),
+}
+
+
+def fit(spec, y, x):
+    model = SARIMAX(
+        y, exog=x if spec["exog"] else None, order=spec["order"], seasonal_order=spec["seasonal_order"], trend=spec["trend"]
+    )
+    return model.fit(disp=False)
+
+
+def estimates(result):
+    """coefficients under the coef_names of this crate, and sigma2"""
+    exog_names = result.model.exog_names or []
+    values = {}
+    for name, value in zip(result.model.param_names, result.params):
+        # exogenous variables are x0, x1, ... by column, as in Model::coef_names
+        values[f"x{exog_names.index(name)}" if name in exog_names else name] = value
+    return values
+
+
+def main():
+    rows = []
+    for case, spec in CASES.items():
+        data = pd.read_csv(f"{case}.csv")
+        y, x = data["y"], data.drop(columns="y")
+        n = len(y) - H
+
+        values = estimates(fit(spec, y, x))
+        train = fit(spec, y[:n], x[:n])
+        preds = np.asarray(train.forecast(H, exog=x[n:] if spec["exog"] else None))
+        sigma = np.sqrt(values["sigma2"])
+
+        for method, tolerance in METHODS.items():
+            for name, value in values.items():
+                rows.append((case, method, "statsmodels", name, value, 0.05 * value if name == "sigma2" else tolerance))
+            for k, value in enumerate(preds):
+                rows.append((case, method, "statsmodels", f"forecast.h{k + 1}", value, 0.5 * sigma))
+
+    header = ["case", "method", "source", "name", "value", "tolerance"]
+    kept = []
+    if os.path.exists("reference.csv"):
+        with open("reference.csv") as f:
+            kept = [row for row in csv.DictReader(f) if row["source"] != "statsmodels"]
+    with open("reference.csv", "w", newline="") as f:
+        writer = csv.writer(f)
+        writer.writerow(header)
+        writer.writerows([row[h] for h in header] for row in kept)
+        writer.writerows(rows)
+
+
+if __name__ == "__main__":
+    main()